@@ -0,0 +1,891 @@
+use anyhow::Context;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt, fs,
+    io::{self, Read},
+    path::{Component, Path, PathBuf},
+};
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipArchive, ZipWriter};
+
+const ZIP_MAGIC: &[u8] = &[0x50, 0x4B];
+const GZIP_MAGIC: &[u8] = &[0x1F, 0x8B];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xB5, 0x2F, 0xFD];
+const SEVEN_ZIP_MAGIC: &[u8] = &[0x37, 0x7A, 0xBC, 0xAF, 0x27, 0x1C];
+
+const S_IFMT: u32 = 0o170000;
+const S_IFREG: u32 = 0o100000;
+const S_IFDIR: u32 = 0o040000;
+const S_IFLNK: u32 = 0o120000;
+
+const FILE_ATTRIBUTE_REPARSE_POINT: u32 = 0x400;
+const FILE_ATTRIBUTE_UNIX_EXTENSION: u32 = 0x8000;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct ExtractionLimits {
+    pub max_total_size: u64,        // Total uncompressed size in bytes
+    pub max_entries: usize,         // Number of files and directories
+    pub max_compression_ratio: u64, // Uncompressed size to archive size
+}
+
+impl Default for ExtractionLimits {
+    fn default() -> Self {
+        Self {
+            max_total_size: 2 * 1024 * 1024 * 1024,
+            max_entries: 100_000,
+            max_compression_ratio: 100,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ExtractionError {
+    UnsafePath(String),
+    Symlink(String),
+    HardLink(String),
+    SpecialFile(String),
+    TooManyEntries(usize),
+    TotalSizeExceeded(u64),
+    CompressionRatioExceeded(u64),
+}
+
+impl fmt::Display for ExtractionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExtractionError::UnsafePath(name) => {
+                write!(f, "Archive entry '{}' points outside of the archive", name)
+            }
+            ExtractionError::Symlink(name) => {
+                write!(
+                    f,
+                    "Archive entry '{}' is a symbolic link, links are not allowed",
+                    name
+                )
+            }
+            ExtractionError::HardLink(name) => {
+                write!(
+                    f,
+                    "Archive entry '{}' is a hard link, links are not allowed",
+                    name
+                )
+            }
+            ExtractionError::SpecialFile(name) => {
+                write!(f, "Archive entry '{}' is not a regular file", name)
+            }
+            ExtractionError::TooManyEntries(limit) => {
+                write!(f, "Archive contains more than {} entries", limit)
+            }
+            ExtractionError::TotalSizeExceeded(limit) => {
+                write!(f, "Archive unpacks to more than {} bytes", limit)
+            }
+            ExtractionError::CompressionRatioExceeded(limit) => {
+                write!(f, "Archive compression ratio is higher than {}", limit)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ExtractionError {}
+
+enum EntryKind {
+    File,
+    Directory,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArchiveType {
+    #[serde(rename = "zip")]
+    Zip,
+    #[serde(rename = "tar.gz")]
+    TarGz,
+    #[serde(rename = "tar.zst")]
+    TarZst,
+    #[serde(rename = "7z")]
+    SevenZip,
+}
+
+impl ArchiveType {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ArchiveType::Zip => "zip",
+            ArchiveType::TarGz => "tar.gz",
+            ArchiveType::TarZst => "tar.zst",
+            ArchiveType::SevenZip => "7z",
+        }
+    }
+
+    fn from_magic(header: &[u8]) -> Option<Self> {
+        if header.starts_with(SEVEN_ZIP_MAGIC) {
+            Some(ArchiveType::SevenZip)
+        } else if header.starts_with(ZSTD_MAGIC) {
+            Some(ArchiveType::TarZst)
+        } else if header.starts_with(GZIP_MAGIC) {
+            Some(ArchiveType::TarGz)
+        } else if header.starts_with(ZIP_MAGIC) {
+            Some(ArchiveType::Zip)
+        } else {
+            None
+        }
+    }
+}
+
+/// Detects the archive type by its magic bytes, falling back to the manifest
+/// hint when the header is not recognized.
+pub fn detect_archive_type(
+    archive_path: &Path,
+    hint: Option<ArchiveType>,
+) -> Result<ArchiveType, anyhow::Error> {
+    let mut file = fs::File::open(archive_path)
+        .with_context(|| format!("Failed to open archive {:?}", archive_path))?;
+
+    let mut header = Vec::with_capacity(SEVEN_ZIP_MAGIC.len());
+    file.by_ref()
+        .take(SEVEN_ZIP_MAGIC.len() as u64)
+        .read_to_end(&mut header)
+        .with_context(|| format!("Failed to read archive header {:?}", archive_path))?;
+
+    match (ArchiveType::from_magic(&header), hint) {
+        (Some(detected), Some(hint)) if detected != hint => {
+            warn!(
+                "Archive {:?} looks like {:?} but manifest says {:?}, using detected type",
+                archive_path, detected, hint
+            );
+            Ok(detected)
+        }
+        (Some(detected), _) => Ok(detected),
+        (None, Some(hint)) => {
+            debug!("Unknown archive header, using manifest hint {:?}", hint);
+            Ok(hint)
+        }
+        (None, None) => Err(anyhow::anyhow!(
+            "Unsupported archive type of {:?}",
+            archive_path
+        )),
+    }
+}
+
+/// Lists entry paths without unpacking anything to disk. Entries with unsafe
+/// paths are left out here and rejected during extraction.
+pub fn list_archive_entries(
+    archive_path: &Path,
+    hint: Option<ArchiveType>,
+) -> Result<Vec<PathBuf>, anyhow::Error> {
+    let mut entries = Vec::new();
+
+    match detect_archive_type(archive_path, hint)? {
+        ArchiveType::Zip => {
+            let file =
+                fs::File::open(archive_path).with_context(|| format!("Failed to open zip file"))?;
+            let mut archive =
+                ZipArchive::new(file).with_context(|| format!("Failed to read ZIP archive"))?;
+
+            for i in 0..archive.len() {
+                let file = archive
+                    .by_index_raw(i)
+                    .with_context(|| format!("Error reading file in zip"))?;
+                entries.extend(file.enclosed_name());
+            }
+        }
+        archive_type @ (ArchiveType::TarGz | ArchiveType::TarZst) => {
+            let mut archive = open_tar_archive(archive_path, archive_type)?;
+
+            for entry in archive
+                .entries()
+                .with_context(|| format!("Failed to read TAR archive"))?
+            {
+                let entry = entry.with_context(|| format!("Error reading file in tar"))?;
+                let entry_path = entry
+                    .path()
+                    .with_context(|| format!("Error reading path of tar entry"))?;
+                entries.extend(enclosed_path(&entry_path));
+            }
+        }
+        ArchiveType::SevenZip => {
+            let archive = sevenz_rust::Archive::open(archive_path)
+                .with_context(|| format!("Failed to read 7z archive"))?;
+
+            for entry in archive.files.iter().filter(|entry| !entry.is_anti_item()) {
+                entries.extend(enclosed_path(&PathBuf::from(
+                    entry.name().replace('\\', "/"),
+                )));
+            }
+        }
+    }
+
+    entries.retain(|entry| !entry.as_os_str().is_empty());
+    Ok(entries)
+}
+
+/// Extracts the entries below `subtree` straight into `extract_path`,
+/// everything else in the archive is skipped. Files are only written if
+/// `include` accepts their path relative to `subtree`.
+pub fn extract_archive(
+    archive_path: &Path,
+    extract_path: &Path,
+    hint: Option<ArchiveType>,
+    limits: &ExtractionLimits,
+    subtree: &Path,
+    include: &dyn Fn(&Path) -> bool,
+) -> Result<(), anyhow::Error> {
+    let archive_type = detect_archive_type(archive_path, hint)?;
+    debug!(
+        "Extracting {:?} of {:?} archive {:?}",
+        subtree, archive_type, archive_path
+    );
+
+    let archive_size = fs::metadata(archive_path)
+        .with_context(|| format!("Failed to get archive size"))?
+        .len();
+    let mut extractor = Extractor::new(extract_path, subtree, include, archive_size, limits);
+
+    match archive_type {
+        ArchiveType::Zip => extract_zip_archive(archive_path, &mut extractor),
+        ArchiveType::TarGz | ArchiveType::TarZst => {
+            let archive = open_tar_archive(archive_path, archive_type)?;
+            extract_tar_archive(archive, &mut extractor)
+        }
+        ArchiveType::SevenZip => extract_7z_archive(archive_path, &mut extractor),
+    }
+}
+
+/// Packs a folder into a zip archive so it can be installed and cached like
+/// a downloaded one. Links and paths rejected by `include` are skipped.
+pub fn create_zip_archive(
+    source_dir: &Path,
+    archive_path: &Path,
+    include: &dyn Fn(&Path) -> bool,
+) -> Result<(), anyhow::Error> {
+    let file = fs::File::create(archive_path)
+        .with_context(|| format!("Failed to create archive {:?}", archive_path))?;
+    let mut writer = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    let mut pending = vec![source_dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        for entry in fs::read_dir(&current)
+            .with_context(|| format!("Failed to read directory {:?}", current))?
+        {
+            let entry = entry.with_context(|| format!("Error reading entry in {:?}", current))?;
+            let path = entry.path();
+            let file_type = entry
+                .file_type()
+                .with_context(|| format!("Failed to get file type of {:?}", path))?;
+
+            let relative = path.strip_prefix(source_dir).unwrap_or(&path);
+            if !include(relative) {
+                debug!("Not packing {:?}", path);
+                continue;
+            }
+            let name = relative.to_string_lossy().replace('\\', "/");
+
+            if file_type.is_dir() {
+                writer
+                    .add_directory(name, options)
+                    .with_context(|| format!("Failed to add directory {:?} to archive", path))?;
+                pending.push(path);
+            } else if file_type.is_file() {
+                debug!("Packing {:?}", path);
+                writer
+                    .start_file(name, options)
+                    .with_context(|| format!("Failed to add file {:?} to archive", path))?;
+                let mut source = fs::File::open(&path)
+                    .with_context(|| format!("Failed to open file {:?}", path))?;
+                io::copy(&mut source, &mut writer)
+                    .with_context(|| format!("Failed to pack file {:?}", path))?;
+            } else {
+                warn!("Skipping {:?}, only files and folders are packed", path);
+            }
+        }
+    }
+
+    writer
+        .finish()
+        .with_context(|| format!("Failed to finish archive {:?}", archive_path))?;
+    Ok(())
+}
+
+fn open_tar_archive(
+    archive_path: &Path,
+    archive_type: ArchiveType,
+) -> Result<tar::Archive<Box<dyn Read>>, anyhow::Error> {
+    let file = fs::File::open(archive_path)
+        .with_context(|| format!("Failed to open archive {:?}", archive_path))?;
+    let file = io::BufReader::new(file);
+
+    let reader: Box<dyn Read> = match archive_type {
+        ArchiveType::TarGz => Box::new(flate2::read::GzDecoder::new(file)),
+        ArchiveType::TarZst => Box::new(
+            zstd::stream::read::Decoder::new(file)
+                .with_context(|| format!("Failed to initialize zstd decoder"))?,
+        ),
+        _ => return Err(anyhow::anyhow!("{:?} is not a TAR archive", archive_type)),
+    };
+
+    Ok(tar::Archive::new(reader))
+}
+
+/// Returns the entry path relative to the extraction root, or `None` if it
+/// is absolute or escapes the root. The root itself is an empty path.
+fn enclosed_path(path: &Path) -> Option<PathBuf> {
+    let mut enclosed = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => enclosed.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+
+    Some(enclosed)
+}
+
+/// Maps a unix mode to the kind of entry we are willing to extract.
+fn entry_kind_from_mode(name: &str, mode: u32) -> Result<Option<EntryKind>, ExtractionError> {
+    match mode & S_IFMT {
+        0 => Ok(None),
+        S_IFREG => Ok(Some(EntryKind::File)),
+        S_IFDIR => Ok(Some(EntryKind::Directory)),
+        S_IFLNK => Err(ExtractionError::Symlink(name.to_string())),
+        _ => Err(ExtractionError::SpecialFile(name.to_string())),
+    }
+}
+
+/// Writes archive entries below the extraction root while enforcing
+/// the configured limits.
+struct Extractor<'a> {
+    extract_path: &'a Path,
+    subtree: &'a Path,
+    include: &'a dyn Fn(&Path) -> bool,
+    limits: &'a ExtractionLimits,
+    max_size: u64,
+    entries: usize,
+    total_size: u64,
+}
+
+impl<'a> Extractor<'a> {
+    fn new(
+        extract_path: &'a Path,
+        subtree: &'a Path,
+        include: &'a dyn Fn(&Path) -> bool,
+        archive_size: u64,
+        limits: &'a ExtractionLimits,
+    ) -> Self {
+        let max_size = limits
+            .max_total_size
+            .min(archive_size.saturating_mul(limits.max_compression_ratio));
+
+        Self {
+            extract_path,
+            subtree,
+            include,
+            limits,
+            max_size,
+            entries: 0,
+            total_size: 0,
+        }
+    }
+
+    fn size_error(&self) -> ExtractionError {
+        if self.total_size > self.limits.max_total_size {
+            ExtractionError::TotalSizeExceeded(self.limits.max_total_size)
+        } else {
+            ExtractionError::CompressionRatioExceeded(self.limits.max_compression_ratio)
+        }
+    }
+
+    fn relative_path(&mut self, name: &str, path: &Path) -> Result<Option<PathBuf>, anyhow::Error> {
+        self.entries += 1;
+        if self.entries > self.limits.max_entries {
+            return Err(ExtractionError::TooManyEntries(self.limits.max_entries).into());
+        }
+
+        let enclosed =
+            enclosed_path(path).ok_or_else(|| ExtractionError::UnsafePath(name.to_string()))?;
+
+        match enclosed.strip_prefix(self.subtree) {
+            Ok(relative) if !relative.as_os_str().is_empty() => Ok(Some(relative.to_path_buf())),
+            _ => Ok(None),
+        }
+    }
+
+    /// Returns `false` if the entry was skipped and its data was not consumed.
+    fn extract_entry(
+        &mut self,
+        name: &str,
+        path: &Path,
+        kind: EntryKind,
+        reader: &mut dyn Read,
+    ) -> Result<bool, anyhow::Error> {
+        let Some(relative) = self.relative_path(name, path)? else {
+            debug!("Skipping entry outside of {:?}: {:?}", self.subtree, name);
+            return Ok(false);
+        };
+
+        if matches!(kind, EntryKind::File) && !(self.include)(&relative) {
+            debug!("Skipping entry that was not selected: {:?}", name);
+            return Ok(false);
+        }
+
+        let outpath = self.extract_path.join(relative);
+        match kind {
+            EntryKind::Directory => self.extract_directory(&outpath)?,
+            EntryKind::File => self.extract_file(reader, &outpath)?,
+        }
+
+        Ok(true)
+    }
+
+    fn extract_file(&mut self, reader: &mut dyn Read, outpath: &Path) -> Result<(), anyhow::Error> {
+        debug!("Extracting file: {:?}", outpath);
+        if let Some(parent) = outpath.parent() {
+            if !parent.exists() {
+                fs::create_dir_all(parent).with_context(|| {
+                    format!("Failed to create parent directory during extraction")
+                })?;
+            }
+        }
+
+        let mut outfile = fs::File::create(outpath)
+            .with_context(|| format!("Failed to create file during extraction"))?;
+
+        // Sizes in archive headers can lie, so count what is actually written
+        let remaining = self.max_size.saturating_sub(self.total_size);
+        let written = io::copy(&mut reader.take(remaining.saturating_add(1)), &mut outfile)
+            .with_context(|| format!("Failed to copy file during extraction"))?;
+
+        self.total_size += written;
+        if written > remaining {
+            return Err(self.size_error().into());
+        }
+
+        set_normalized_permissions(outpath, 0o644)
+    }
+
+    fn extract_directory(&mut self, outpath: &Path) -> Result<(), anyhow::Error> {
+        debug!("Creating directory: {:?}", outpath);
+        fs::create_dir_all(outpath)
+            .with_context(|| format!("Failed to create directory during extraction"))?;
+
+        set_normalized_permissions(outpath, 0o755)
+    }
+}
+
+#[cfg(unix)]
+fn set_normalized_permissions(path: &Path, mode: u32) -> Result<(), anyhow::Error> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(mode))
+        .with_context(|| format!("Failed to set permissions"))
+}
+
+#[cfg(not(unix))]
+fn set_normalized_permissions(_path: &Path, _mode: u32) -> Result<(), anyhow::Error> {
+    Ok(())
+}
+
+fn extract_zip_archive(zip_path: &Path, extractor: &mut Extractor) -> Result<(), anyhow::Error> {
+    let file = fs::File::open(zip_path).with_context(|| format!("Failed to open zip file"))?;
+
+    let mut archive =
+        ZipArchive::new(file).with_context(|| format!("Failed to read ZIP archive"))?;
+
+    if archive.len() > extractor.limits.max_entries {
+        return Err(ExtractionError::TooManyEntries(extractor.limits.max_entries).into());
+    }
+
+    for i in 0..archive.len() {
+        let mut file = archive
+            .by_index(i)
+            .with_context(|| format!("Error reading file in zip"))?;
+
+        extract_zip_entry(&mut file, extractor)?;
+    }
+
+    Ok(())
+}
+
+fn extract_zip_entry(
+    file: &mut zip::read::ZipFile,
+    extractor: &mut Extractor,
+) -> Result<(), anyhow::Error> {
+    let name = file.name().to_string();
+
+    if file.is_symlink() {
+        return Err(ExtractionError::Symlink(name).into());
+    }
+
+    let kind = match file.unix_mode() {
+        Some(mode) => entry_kind_from_mode(&name, mode)?,
+        None => None,
+    };
+
+    let kind = if file.is_dir() {
+        EntryKind::Directory
+    } else {
+        kind.unwrap_or(EntryKind::File)
+    };
+
+    let path = file
+        .enclosed_name()
+        .ok_or_else(|| ExtractionError::UnsafePath(name.clone()))?;
+
+    extractor.extract_entry(&name, &path, kind, file)?;
+    Ok(())
+}
+
+fn extract_tar_archive(
+    mut archive: tar::Archive<Box<dyn Read>>,
+    extractor: &mut Extractor,
+) -> Result<(), anyhow::Error> {
+    for entry in archive
+        .entries()
+        .with_context(|| format!("Failed to read TAR archive"))?
+    {
+        let mut entry = entry.with_context(|| format!("Error reading file in tar"))?;
+
+        let entry_path = entry
+            .path()
+            .with_context(|| format!("Error reading path of tar entry"))?
+            .into_owned();
+        let name = entry_path.to_string_lossy().to_string();
+
+        let kind = match entry.header().entry_type() {
+            tar::EntryType::Directory => EntryKind::Directory,
+            tar::EntryType::Regular | tar::EntryType::Continuous => EntryKind::File,
+            tar::EntryType::Symlink => return Err(ExtractionError::Symlink(name).into()),
+            tar::EntryType::Link => return Err(ExtractionError::HardLink(name).into()),
+            tar::EntryType::XGlobalHeader | tar::EntryType::XHeader => continue,
+            _ => return Err(ExtractionError::SpecialFile(name).into()),
+        };
+
+        // Unread data of skipped entries is stepped over by the tar reader
+        extractor.extract_entry(&name, &entry_path, kind, &mut entry)?;
+    }
+
+    Ok(())
+}
+
+fn extract_7z_archive(archive_path: &Path, extractor: &mut Extractor) -> Result<(), anyhow::Error> {
+    let mut archive = sevenz_rust::SevenZReader::open(archive_path, sevenz_rust::Password::empty())
+        .with_context(|| format!("Failed to read 7z archive"))?;
+
+    let mut result = Ok(());
+
+    archive
+        .for_each_entries(|entry, reader| {
+            result = extract_7z_entry(entry, reader, extractor);
+            Ok(result.is_ok())
+        })
+        .with_context(|| format!("Error reading file in 7z"))?;
+
+    result
+}
+
+fn extract_7z_entry(
+    entry: &sevenz_rust::SevenZArchiveEntry,
+    reader: &mut dyn Read,
+    extractor: &mut Extractor,
+) -> Result<(), anyhow::Error> {
+    let name = entry.name().to_string();
+
+    if entry.is_anti_item() {
+        debug!("Skipping 7z anti item {:?}", name);
+        return Ok(());
+    }
+
+    let attributes = entry.windows_attributes();
+    if attributes & FILE_ATTRIBUTE_REPARSE_POINT != 0 {
+        return Err(ExtractionError::Symlink(name).into());
+    }
+
+    let kind = if attributes & FILE_ATTRIBUTE_UNIX_EXTENSION != 0 {
+        entry_kind_from_mode(&name, attributes >> 16)?
+    } else {
+        None
+    };
+
+    let kind = kind.unwrap_or(if entry.is_directory() {
+        EntryKind::Directory
+    } else {
+        EntryKind::File
+    });
+
+    let path = PathBuf::from(name.replace('\\', "/"));
+    if !extractor.extract_entry(&name, &path, kind, reader)? {
+        // Entries share one solid stream, skipped data must still be consumed
+        io::copy(reader, &mut io::sink()).with_context(|| format!("Failed to skip entry in 7z"))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn write_zip(path: &Path, files: &[(&str, &[u8])]) {
+        let mut writer = ZipWriter::new(fs::File::create(path).unwrap());
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+        for (name, data) in files {
+            writer.start_file(*name, options).unwrap();
+            writer.write_all(data).unwrap();
+        }
+        writer.finish().unwrap();
+    }
+
+    fn tar_header(name: &[u8], entry_type: tar::EntryType, size: u64, mode: u32) -> tar::Header {
+        // Names are written raw, `set_path` refuses the unsafe ones
+        let mut header = tar::Header::new_gnu();
+        header.as_gnu_mut().unwrap().name[..name.len()].copy_from_slice(name);
+        header.set_entry_type(entry_type);
+        header.set_size(size);
+        header.set_mode(mode);
+        header.set_cksum();
+        header
+    }
+
+    fn write_tar_gz(path: &Path, entries: &[(tar::Header, &[u8])]) {
+        let encoder =
+            flate2::write::GzEncoder::new(fs::File::create(path).unwrap(), Default::default());
+        let mut builder = tar::Builder::new(encoder);
+
+        for (header, data) in entries {
+            builder.append(header, *data).unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap();
+    }
+
+    fn extract(
+        archive_path: &Path,
+        limits: &ExtractionLimits,
+    ) -> (tempfile::TempDir, Result<(), anyhow::Error>) {
+        let output = tempfile::tempdir().unwrap();
+        let result = extract_archive(
+            archive_path,
+            output.path(),
+            None,
+            limits,
+            Path::new(""),
+            &|_| true,
+        );
+        (output, result)
+    }
+
+    fn extraction_error(result: Result<(), anyhow::Error>) -> ExtractionError {
+        result
+            .expect_err("extraction should fail")
+            .downcast::<ExtractionError>()
+            .expect("not an extraction error")
+    }
+
+    #[test]
+    fn extracts_regular_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive_path = dir.path().join("ok.zip");
+        write_zip(&archive_path, &[("Lang/a.json", b"{}")]);
+
+        let (output, result) = extract(&archive_path, &ExtractionLimits::default());
+        result.unwrap();
+        assert_eq!(fs::read(output.path().join("Lang/a.json")).unwrap(), b"{}");
+    }
+
+    #[test]
+    fn rejects_path_traversal_in_zip() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive_path = dir.path().join("evil.zip");
+        write_zip(&archive_path, &[("../evil.json", b"{}")]);
+
+        let (output, result) = extract(&archive_path, &ExtractionLimits::default());
+        assert!(matches!(
+            extraction_error(result),
+            ExtractionError::UnsafePath(_)
+        ));
+        assert!(!output.path().parent().unwrap().join("evil.json").exists());
+    }
+
+    #[test]
+    fn rejects_path_traversal_in_tar() {
+        let dir = tempfile::tempdir().unwrap();
+
+        for name in [
+            &b"../evil.json"[..],
+            b"/tmp/evil.json",
+            b"a/../../evil.json",
+        ] {
+            let archive_path = dir.path().join("evil.tar.gz");
+            let header = tar_header(name, tar::EntryType::Regular, 2, 0o644);
+            write_tar_gz(&archive_path, &[(header, b"{}")]);
+
+            let (_output, result) = extract(&archive_path, &ExtractionLimits::default());
+            assert!(matches!(
+                extraction_error(result),
+                ExtractionError::UnsafePath(_)
+            ));
+        }
+    }
+
+    #[test]
+    fn rejects_symlinks() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let zip_path = dir.path().join("link.zip");
+        let mut writer = ZipWriter::new(fs::File::create(&zip_path).unwrap());
+        writer
+            .add_symlink("link", "/etc/passwd", SimpleFileOptions::default())
+            .unwrap();
+        writer.finish().unwrap();
+
+        let (_output, result) = extract(&zip_path, &ExtractionLimits::default());
+        assert!(matches!(
+            extraction_error(result),
+            ExtractionError::Symlink(_)
+        ));
+
+        let tar_path = dir.path().join("link.tar.gz");
+        let mut header = tar_header(b"link", tar::EntryType::Symlink, 0, 0o777);
+        header.set_link_name("/etc/passwd").unwrap();
+        header.set_cksum();
+        write_tar_gz(&tar_path, &[(header, b"")]);
+
+        let (_output, result) = extract(&tar_path, &ExtractionLimits::default());
+        assert!(matches!(
+            extraction_error(result),
+            ExtractionError::Symlink(_)
+        ));
+    }
+
+    #[test]
+    fn rejects_hard_links() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive_path = dir.path().join("link.tar.gz");
+
+        let mut header = tar_header(b"link", tar::EntryType::Link, 0, 0o644);
+        header.set_link_name("/etc/passwd").unwrap();
+        header.set_cksum();
+        write_tar_gz(&archive_path, &[(header, b"")]);
+
+        let (_output, result) = extract(&archive_path, &ExtractionLimits::default());
+        assert!(matches!(
+            extraction_error(result),
+            ExtractionError::HardLink(_)
+        ));
+    }
+
+    #[test]
+    fn rejects_special_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive_path = dir.path().join("fifo.tar.gz");
+        write_tar_gz(
+            &archive_path,
+            &[(tar_header(b"fifo", tar::EntryType::Fifo, 0, 0o644), b"")],
+        );
+
+        let (_output, result) = extract(&archive_path, &ExtractionLimits::default());
+        assert!(matches!(
+            extraction_error(result),
+            ExtractionError::SpecialFile(_)
+        ));
+    }
+
+    #[test]
+    fn enforces_entry_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let limits = ExtractionLimits {
+            max_entries: 2,
+            ..Default::default()
+        };
+
+        let zip_path = dir.path().join("many.zip");
+        write_zip(&zip_path, &[("a", b"1"), ("b", b"2"), ("c", b"3")]);
+        let (_output, result) = extract(&zip_path, &limits);
+        assert!(matches!(
+            extraction_error(result),
+            ExtractionError::TooManyEntries(2)
+        ));
+
+        let tar_path = dir.path().join("many.tar.gz");
+        let entries: Vec<(tar::Header, &[u8])> = [b"a", b"b", b"c"]
+            .iter()
+            .map(|name| {
+                (
+                    tar_header(*name, tar::EntryType::Regular, 1, 0o644),
+                    &b"1"[..],
+                )
+            })
+            .collect();
+        write_tar_gz(&tar_path, &entries);
+        let (_output, result) = extract(&tar_path, &limits);
+        assert!(matches!(
+            extraction_error(result),
+            ExtractionError::TooManyEntries(2)
+        ));
+    }
+
+    #[test]
+    fn enforces_total_size_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive_path = dir.path().join("big.zip");
+        write_zip(&archive_path, &[("a", &[1; 64]), ("b", &[2; 64])]);
+
+        let limits = ExtractionLimits {
+            max_total_size: 100,
+            ..Default::default()
+        };
+        let (_output, result) = extract(&archive_path, &limits);
+        assert!(matches!(
+            extraction_error(result),
+            ExtractionError::TotalSizeExceeded(100)
+        ));
+    }
+
+    #[test]
+    fn enforces_compression_ratio_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive_path = dir.path().join("bomb.zip");
+        write_zip(&archive_path, &[("zeros", &vec![0; 4 * 1024 * 1024])]);
+
+        let (_output, result) = extract(&archive_path, &ExtractionLimits::default());
+        assert!(matches!(
+            extraction_error(result),
+            ExtractionError::CompressionRatioExceeded(100)
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn strips_permission_bits() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let archive_path = dir.path().join("modes.tar.gz");
+        write_tar_gz(
+            &archive_path,
+            &[
+                (
+                    tar_header(b"bin/", tar::EntryType::Directory, 0, 0o7777),
+                    b"",
+                ),
+                (
+                    tar_header(b"bin/run", tar::EntryType::Regular, 2, 0o6777),
+                    b"{}",
+                ),
+            ],
+        );
+
+        let (output, result) = extract(&archive_path, &ExtractionLimits::default());
+        result.unwrap();
+
+        let mode = |path: &str| {
+            fs::metadata(output.path().join(path))
+                .unwrap()
+                .permissions()
+                .mode()
+                & 0o7777
+        };
+        assert_eq!(mode("bin"), 0o755);
+        assert_eq!(mode("bin/run"), 0o644);
+    }
+}
@@ -0,0 +1,1064 @@
+use crate::{
+    group_by_key, launch, settings, steam, utils, validation, verify_after_game_update,
+    UpdateResult, UpdateStatus,
+};
+use anyhow::Context;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+// Name of a link to the binary that behaves like `wrap`
+const WRAPPER_NAME: &str = "llm-wrapper";
+
+const USAGE: &str = "Usage: limbus-localization-manager <command> [--json]
+
+Commands:
+  sources              List configured sources, the selected one is marked
+  list                 List localizations of the selected source
+  install <id>         Install a localization, all sub-packs for a bundle
+  uninstall <id>       Remove an installed localization and its cache
+  update               Update installed localizations from their sources
+  verify               Check installed files without changing them
+  set-game-dir [path]  Set the game directory, detect it from Steam if omitted
+  play                 Update, then launch the game
+  test-launch          Show the command play would run, without running it
+  wrap <command...>    Update, then run the given command, for Steam launch
+                       options as `wrap %command%`
+  help                 Show this message";
+
+const COMMANDS: &[&str] = &[
+    "sources",
+    "list",
+    "install",
+    "uninstall",
+    "update",
+    "verify",
+    "set-game-dir",
+    "play",
+    "test-launch",
+    "wrap",
+    "help",
+];
+
+/// What the process was started to do.
+#[derive(Debug, PartialEq)]
+pub enum Invocation<'a> {
+    Wrap(&'a [String]), // Started through the wrapper link, args are the game command
+    Command(&'a [String]), // A CLI command and its arguments
+    Gui,
+}
+
+/// Decides between the wrapper, the CLI and the GUI from the program name
+/// and the arguments after it.
+pub fn parse_invocation<'a>(program: Option<&str>, args: &'a [String]) -> Invocation<'a> {
+    if program.is_some_and(is_wrapper) {
+        return Invocation::Wrap(args);
+    }
+
+    if args
+        .iter()
+        .find(|arg| *arg != "--json")
+        .is_some_and(|arg| is_command(arg))
+    {
+        return Invocation::Command(args);
+    }
+
+    Invocation::Gui
+}
+
+fn is_command(arg: &str) -> bool {
+    COMMANDS.contains(&arg) || arg == "--help"
+}
+
+fn is_wrapper(program: &str) -> bool {
+    Path::new(program)
+        .file_stem()
+        .is_some_and(|stem| stem == WRAPPER_NAME)
+}
+
+/// Runs a command without the webview and returns the process exit code.
+/// Config and installed metadata are the same files the GUI uses.
+pub fn run(args: &[String]) -> i32 {
+    // Everything after `wrap` belongs to the game command
+    if args.first().is_some_and(|arg| arg == "wrap") {
+        return wrap(&args[1..]);
+    }
+
+    let (args, json) = split_json_flag(args);
+
+    let result = tokio::runtime::Runtime::new()
+        .context("Failed to start async runtime")
+        .and_then(|runtime| runtime.block_on(execute(&args, json)));
+
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            if json {
+                println!("{}", serde_json::json!({ "error": format!("{:#}", e) }));
+            } else {
+                eprintln!("error: {:#}", e);
+            }
+            1
+        }
+    }
+}
+
+/// Command arguments without `--json`, which may appear anywhere, and
+/// whether it was given.
+fn split_json_flag(args: &[String]) -> (Vec<&str>, bool) {
+    let json = args.iter().any(|arg| arg == "--json");
+    let args = args
+        .iter()
+        .map(String::as_str)
+        .filter(|arg| *arg != "--json")
+        .collect();
+    (args, json)
+}
+
+async fn execute(args: &[&str], json: bool) -> anyhow::Result<()> {
+    let mut cli = Cli {
+        settings: settings::load_headless_settings().context("Failed to load settings")?,
+        json,
+        quiet: false,
+        deadline: None,
+    };
+
+    match args {
+        ["help"] | ["--help"] => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        ["sources"] => cli.sources(),
+        ["list"] => cli.list().await,
+        ["install", id] => cli.install(id).await,
+        ["uninstall", id] => cli.uninstall(id).await,
+        ["update"] => cli.update().await.map(|_| ()),
+        ["verify"] => cli.verify(),
+        ["set-game-dir"] => cli.set_game_directory(None),
+        ["set-game-dir", path] => cli.set_game_directory(Some(path)),
+        ["play"] => cli.play().await,
+        ["test-launch"] => cli.test_launch(),
+        _ => Err(anyhow::anyhow!("Invalid arguments\n\n{}", USAGE)),
+    }
+}
+
+#[derive(Serialize)]
+struct SourceEntry<'a> {
+    id: &'a str,
+    name: &'a str,
+    url: &'a str,
+    selected: bool,
+}
+
+#[derive(Serialize)]
+struct ListEntry {
+    id: String,
+    name: String,
+    version: Option<String>,           // Latest in the selected source
+    installed_version: Option<String>, // Recorded in installed metadata
+    pinned: bool,
+    active: bool, // Set in the game's config.json
+}
+
+#[derive(Serialize)]
+struct VerifyEntry {
+    id: String,
+    present: bool, // Lang/<id> exists
+    issues: Vec<validation::ValidationIssue>,
+}
+
+struct Cli {
+    settings: settings::AppSettings,
+    json: bool,
+    quiet: bool,               // Nothing is printed but warnings
+    deadline: Option<Instant>, // Set by the wrapper, no new work starts after it
+}
+
+/// Commands that write settings or installed metadata would be overwritten
+/// by a running GUI, which keeps both in memory.
+fn ensure_gui_closed() -> anyhow::Result<()> {
+    match settings::gui_lock_holder() {
+        Some(pid) => Err(anyhow::anyhow!(
+            "Limbus Localization Manager is open (pid {}), close it first",
+            pid
+        )),
+        None => Ok(()),
+    }
+}
+
+impl Cli {
+    fn print<T: Serialize>(&self, value: &T, human: impl FnOnce() -> String) {
+        if self.quiet {
+            return;
+        }
+
+        if self.json {
+            match serde_json::to_string_pretty(value) {
+                Ok(output) => println!("{}", output),
+                Err(e) => eprintln!("error: Failed to serialize output: {}", e),
+            }
+        } else {
+            println!("{}", human());
+        }
+    }
+
+    /// Runs `future` until the deadline. It can only be cut at an await, so a
+    /// download is dropped while an install that started is left to finish.
+    async fn within_deadline<T>(
+        &self,
+        future: impl Future<Output = anyhow::Result<T>>,
+    ) -> anyhow::Result<T> {
+        let Some(deadline) = self.deadline else {
+            return future.await;
+        };
+
+        if Instant::now() >= deadline {
+            return Err(anyhow::anyhow!("Out of time for updates"));
+        }
+
+        tokio::time::timeout_at(deadline.into(), future)
+            .await
+            .map_err(|_| anyhow::anyhow!("Out of time for updates"))?
+    }
+
+    fn game_path(&self) -> anyhow::Result<PathBuf> {
+        match &self.settings.game_directory {
+            Some(dir) => Ok(PathBuf::from(dir)),
+            None => steam::get_game_directory(),
+        }
+    }
+
+    fn selected_source(&self) -> anyhow::Result<(String, String)> {
+        let name = self
+            .settings
+            .selected_source
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("No active source selected"))?;
+
+        let source = self
+            .settings
+            .sources
+            .get(name)
+            .ok_or_else(|| anyhow::anyhow!("No active source selected"))?;
+
+        Ok((name.clone(), source.url.clone()))
+    }
+
+    fn sources(&self) -> anyhow::Result<()> {
+        let mut sources: Vec<SourceEntry> = self
+            .settings
+            .sources
+            .iter()
+            .map(|(id, source)| SourceEntry {
+                id,
+                name: &source.name,
+                url: &source.url,
+                selected: self.settings.selected_source.as_ref() == Some(id),
+            })
+            .collect();
+        sources.sort_by(|a, b| a.id.cmp(b.id));
+
+        self.print(&sources, || {
+            sources
+                .iter()
+                .map(|source| {
+                    let marker = if source.selected { "*" } else { " " };
+                    format!(
+                        "{} {:<20} {:<24} {}",
+                        marker, source.id, source.name, source.url
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        });
+        Ok(())
+    }
+
+    async fn list(&self) -> anyhow::Result<()> {
+        let (_, source_url) = self.selected_source()?;
+        let remote = utils::fetch_available_localizations(&source_url).await?;
+
+        let game_path = self.game_path().ok();
+        let metadata = match &game_path {
+            Some(game_path) => Some(utils::load_installed_metadata(game_path)?),
+            None => None,
+        };
+        let active = game_path
+            .as_ref()
+            .and_then(|game_path| utils::get_active_localization(game_path).ok().flatten());
+
+        let installed = |id: &str| {
+            metadata
+                .as_ref()
+                .and_then(|metadata| metadata.installed.get(id))
+        };
+
+        let mut entries: Vec<ListEntry> = remote
+            .iter()
+            .flat_map(|localization| localization.selected_packs(None).unwrap_or_default())
+            .map(|localization| ListEntry {
+                installed_version: installed(&localization.id).map(|i| i.version.clone()),
+                pinned: installed(&localization.id).is_some_and(|i| i.pinned),
+                active: active.as_ref() == Some(&localization.id),
+                id: localization.id,
+                name: localization.name,
+                version: Some(localization.version),
+            })
+            .collect();
+
+        // Installed from another source, locally or in dev mode
+        for installed in metadata
+            .iter()
+            .flat_map(|metadata| metadata.installed.values())
+        {
+            if entries.iter().any(|entry| entry.id == installed.id) {
+                continue;
+            }
+
+            entries.push(ListEntry {
+                id: installed.id.clone(),
+                name: installed
+                    .snapshots
+                    .first()
+                    .map_or_else(|| installed.id.clone(), |s| s.localization.name.clone()),
+                version: None,
+                installed_version: Some(installed.version.clone()),
+                pinned: installed.pinned,
+                active: active.as_ref() == Some(&installed.id),
+            });
+        }
+
+        self.print(&entries, || {
+            entries
+                .iter()
+                .map(|entry| {
+                    let marker = if entry.active { "*" } else { " " };
+                    // Pinned entries are never updated, whatever the latest version
+                    let status = match (&entry.installed_version, &entry.version) {
+                        (None, _) => String::new(),
+                        (Some(installed), _) if entry.pinned => {
+                            format!("installed {}, pinned", installed)
+                        }
+                        (Some(installed), Some(latest)) if installed != latest => {
+                            format!("installed {}, update available", installed)
+                        }
+                        (Some(installed), _) => format!("installed {}", installed),
+                    };
+
+                    format!(
+                        "{} {:<20} {:<28} {:<12} {}",
+                        marker,
+                        entry.id,
+                        entry.name,
+                        entry.version.as_deref().unwrap_or("-"),
+                        status
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        });
+        Ok(())
+    }
+
+    async fn install(&self, localization_id: &str) -> anyhow::Result<()> {
+        ensure_gui_closed()?;
+        if steam::is_game_running(self.game_path().ok().as_deref()) {
+            return Err(anyhow::anyhow!("Game is running"));
+        }
+
+        let game_path = self.game_path()?;
+        let mut metadata = utils::load_installed_metadata(&game_path)?;
+        if metadata.is_dev_folder(localization_id) {
+            return Err(anyhow::anyhow!(
+                "Localization '{}' is in dev mode",
+                localization_id
+            ));
+        }
+
+        let (source, source_url) = self.selected_source()?;
+        let remote = utils::fetch_available_localizations(&source_url).await?;
+
+        let localizations = match remote.iter().find(|l| l.id == localization_id) {
+            Some(localization) => localization.selected_packs(None)?,
+            None => vec![remote
+                .iter()
+                .find_map(|l| l.resolve(localization_id))
+                .ok_or_else(|| {
+                    anyhow::anyhow!("Localization '{}' not found in source", localization_id)
+                })?],
+        };
+
+        let targets: Vec<(utils::Localization, Option<Vec<String>>)> = localizations
+            .iter()
+            .map(|localization| {
+                let components = metadata
+                    .installed
+                    .get(&localization.id)
+                    .and_then(|installed| installed.components.clone());
+                (localization.clone(), components)
+            })
+            .collect();
+
+        let results = utils::install_shared_archive(
+            &game_path,
+            &metadata,
+            &targets,
+            &self.settings.extraction_limits,
+            &self.settings.validation,
+        )
+        .await?;
+
+        let mut summary = Vec::new();
+        for ((localization, components), result) in targets.into_iter().zip(results) {
+            let result = result.and_then(|report| {
+                self.report_issues(&localization.id, &report.issues);
+                self.record_install(
+                    &game_path,
+                    &mut metadata,
+                    &localization,
+                    &source,
+                    report,
+                    components,
+                )
+            });
+
+            summary.push(match result {
+                Ok(()) => UpdateResult::new(&localization.id, UpdateStatus::Updated),
+                Err(e) => UpdateResult::failed(&localization.id, &e),
+            });
+        }
+
+        self.print_summary(&summary);
+
+        let first_installed = summary
+            .first()
+            .is_some_and(|result| matches!(result.status, UpdateStatus::Updated));
+        if self.settings.activate_after_install && first_installed {
+            utils::set_active_localization(&game_path, &metadata, &localizations[0].id)?;
+        }
+
+        let failed = summary
+            .iter()
+            .filter(|result| matches!(result.status, UpdateStatus::Failed))
+            .count();
+        if failed > 0 {
+            return Err(anyhow::anyhow!(
+                "Failed to install {} localization(s)",
+                failed
+            ));
+        }
+        Ok(())
+    }
+
+    async fn uninstall(&self, localization_id: &str) -> anyhow::Result<()> {
+        ensure_gui_closed()?;
+        if steam::is_game_running(self.game_path().ok().as_deref()) {
+            return Err(anyhow::anyhow!("Game is running"));
+        }
+
+        let game_path = self.game_path()?;
+        let mut metadata = utils::load_installed_metadata(&game_path)?;
+
+        let localization = metadata
+            .installed
+            .get(localization_id)
+            .or_else(|| metadata.stashed.get(localization_id))
+            .and_then(|installed| installed.snapshots.first())
+            .map(|snapshot| snapshot.localization.clone())
+            .ok_or_else(|| {
+                anyhow::anyhow!("Localization '{}' is not installed", localization_id)
+            })?;
+
+        utils::uninstall_localization(&game_path, &localization).await?;
+        let mut summary = vec![UpdateResult::new(localization_id, UpdateStatus::Removed)];
+
+        // Overlays cannot be rebuilt without their base, they are removed with it
+        for overlay in metadata.dependent_overlay_localizations(localization_id) {
+            match utils::uninstall_localization(&game_path, &overlay).await {
+                Ok(()) => summary.push(UpdateResult::new(&overlay.id, UpdateStatus::Removed)),
+                Err(e) => summary.push(UpdateResult::failed(&overlay.id, &e)),
+            }
+        }
+
+        for result in &summary {
+            if matches!(result.status, UpdateStatus::Removed) {
+                metadata.installed.remove(&result.id);
+                metadata.stashed.remove(&result.id);
+            }
+        }
+        utils::save_installed_metadata(&game_path, &metadata)?;
+
+        self.print_summary(&summary);
+        Ok(())
+    }
+
+    /// Same rules as update and play in the GUI: dev mode, local and pinned
+    /// localizations are left alone.
+    async fn update(&self) -> anyhow::Result<Vec<UpdateResult>> {
+        ensure_gui_closed()?;
+        if steam::is_game_running(self.game_path().ok().as_deref()) {
+            return Err(anyhow::anyhow!("Game is running"));
+        }
+
+        let game_path = self.game_path()?;
+        let mut metadata = utils::load_installed_metadata(&game_path)?;
+
+        let mut installed: Vec<utils::InstalledLocalization> =
+            metadata.installed.values().cloned().collect();
+        installed.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let mut remote_by_source: HashMap<String, anyhow::Result<Vec<utils::Localization>>> =
+            HashMap::new();
+        let mut summary = Vec::new();
+        let mut updates = Vec::new();
+
+        for installed in installed {
+            let status = if installed.dev_folder.is_some() {
+                Some(UpdateStatus::DevMode)
+            } else if installed.source == utils::LOCAL_SOURCE {
+                Some(UpdateStatus::Local)
+            } else if installed.pinned {
+                Some(UpdateStatus::Pinned)
+            } else {
+                None
+            };
+
+            if let Some(status) = status {
+                summary.push(UpdateResult::new(&installed.id, status));
+                continue;
+            }
+
+            if !remote_by_source.contains_key(&installed.source) {
+                let remote = match self.settings.sources.get(&installed.source) {
+                    Some(source) => {
+                        self.within_deadline(utils::fetch_available_localizations(&source.url))
+                            .await
+                    }
+                    None => Err(anyhow::anyhow!(
+                        "Source '{}' is not configured",
+                        installed.source
+                    )),
+                };
+                remote_by_source.insert(installed.source.clone(), remote);
+            }
+
+            let remote = match &remote_by_source[&installed.source] {
+                Ok(remote) => remote.iter().find_map(|l| l.resolve(&installed.id)),
+                Err(e) => {
+                    if self.settings.strict_updates {
+                        return Err(anyhow::anyhow!("{:#}", e));
+                    }
+                    summary.push(UpdateResult::failed(
+                        &installed.id,
+                        &anyhow::anyhow!("{:#}", e),
+                    ));
+                    continue;
+                }
+            };
+
+            let Some(remote) = remote else {
+                summary.push(UpdateResult::new(&installed.id, UpdateStatus::Unknown));
+                continue;
+            };
+
+            let localization_path = game_path
+                .join("LimbusCompany_Data")
+                .join("Lang")
+                .join(&installed.id);
+
+            if localization_path.exists() && remote.version == installed.version {
+                summary.push(UpdateResult::new(&installed.id, UpdateStatus::UpToDate));
+                continue;
+            }
+
+            if !self.json && !self.quiet {
+                println!(
+                    "Updating {} {} -> {}",
+                    installed.id, installed.version, remote.version
+                );
+            }
+            updates.push((installed, remote));
+        }
+
+        // Sub-packs of a bundle share one archive, it is downloaded once
+        let groups = group_by_key(updates, |(installed, remote)| {
+            (
+                installed.source.clone(),
+                remote.url.clone(),
+                remote.version.clone(),
+            )
+        });
+
+        for group in groups {
+            let targets: Vec<(utils::Localization, Option<Vec<String>>)> = group
+                .iter()
+                .map(|(installed, remote)| (remote.clone(), installed.components.clone()))
+                .collect();
+
+            let results = match self
+                .within_deadline(utils::install_shared_archive(
+                    &game_path,
+                    &metadata,
+                    &targets,
+                    &self.settings.extraction_limits,
+                    &self.settings.validation,
+                ))
+                .await
+            {
+                Ok(results) => results,
+                Err(e) => targets
+                    .iter()
+                    .map(|_| Err(anyhow::anyhow!("{:#}", e)))
+                    .collect(),
+            };
+
+            for ((installed, remote), result) in group.iter().zip(results) {
+                let result = result.and_then(|report| {
+                    self.report_issues(&installed.id, &report.issues);
+                    self.record_install(
+                        &game_path,
+                        &mut metadata,
+                        remote,
+                        &installed.source,
+                        report,
+                        installed.components.clone(),
+                    )
+                });
+
+                summary.push(match result {
+                    Ok(()) => UpdateResult::new(&installed.id, UpdateStatus::Updated),
+                    Err(e) => UpdateResult::failed(&installed.id, &e),
+                });
+            }
+        }
+        summary.sort_by(|a, b| a.id.cmp(&b.id));
+
+        self.print_summary(&summary);
+
+        let failed = summary
+            .iter()
+            .filter(|result| matches!(result.status, UpdateStatus::Failed))
+            .count();
+
+        if self.settings.strict_updates && failed > 0 {
+            return Err(anyhow::anyhow!(
+                "Failed to update {} localization(s)",
+                failed
+            ));
+        }
+
+        Ok(summary)
+    }
+
+    fn verify(&self) -> anyhow::Result<()> {
+        let game_path = self.game_path()?;
+        let metadata = utils::load_installed_metadata(&game_path)?;
+
+        // Report only, files are not normalized
+        let settings = validation::ValidationSettings {
+            normalize: false,
+            refuse_invalid: false,
+        };
+
+        let mut ids: Vec<&String> = metadata
+            .installed
+            .keys()
+            .chain(metadata.composites.keys())
+            .collect();
+        ids.sort();
+
+        let mut entries = Vec::new();
+        for id in ids {
+            let path = game_path.join("LimbusCompany_Data").join("Lang").join(id);
+            let present = path.is_dir();
+            let issues = if present {
+                validation::validate_directory(&path, &settings)?
+            } else {
+                Vec::new()
+            };
+
+            entries.push(VerifyEntry {
+                id: id.clone(),
+                present,
+                issues,
+            });
+        }
+
+        self.print(&entries, || {
+            entries
+                .iter()
+                .map(|entry| {
+                    if !entry.present {
+                        return format!("{}: missing", entry.id);
+                    }
+                    if entry.issues.is_empty() {
+                        return format!("{}: ok", entry.id);
+                    }
+
+                    std::iter::once(format!("{}: {} issue(s)", entry.id, entry.issues.len()))
+                        .chain(entry.issues.iter().map(|issue| format!("  {}", issue)))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        });
+
+        let broken = entries
+            .iter()
+            .filter(|entry| !entry.present || validation::has_errors(&entry.issues))
+            .count();
+
+        if broken > 0 {
+            return Err(anyhow::anyhow!(
+                "{} localization(s) failed verification",
+                broken
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn set_game_directory(&mut self, directory: Option<&str>) -> anyhow::Result<()> {
+        ensure_gui_closed()?;
+        if let Some(directory) = directory {
+            steam::validate_game_directory(directory)?;
+        }
+
+        self.settings.game_directory = directory.map(str::to_string);
+        settings::save_headless_settings(&self.settings)?;
+
+        let game_path = self.game_path()?;
+        utils::load_installed_metadata(&game_path)?;
+
+        self.print(&serde_json::json!({ "game_directory": game_path }), || {
+            format!("Game directory: {}", game_path.display())
+        });
+        Ok(())
+    }
+
+    async fn play(&self) -> anyhow::Result<()> {
+        let game_path = self.game_path()?;
+
+        if let Err(e) = self.check_game_build().await {
+            eprintln!("warning: Skipping game build check: {:#}", e);
+        }
+        self.update().await?;
+
+        if let Err(e) = utils::validate_game_config(&game_path) {
+            eprintln!("warning: Failed to validate game config: {:#}", e);
+        }
+
+        launch::launch_game(&self.settings.launch, Some(&game_path))
+    }
+
+    /// Verifies and repairs installed localizations when the game build
+    /// changed since it was seen last.
+    async fn check_game_build(&self) -> anyhow::Result<()> {
+        ensure_gui_closed()?;
+        let game_path = self.game_path()?;
+        let mut metadata = utils::load_installed_metadata(&game_path)?;
+
+        let Some(build) = steam::read_game_manifest(&game_path)?.buildid else {
+            return Ok(());
+        };
+        if metadata.game_build.as_ref() == Some(&build) {
+            return Ok(());
+        }
+
+        if steam::is_game_running(Some(&game_path)) {
+            return Err(anyhow::anyhow!("Game is running"));
+        }
+
+        if let Some(previous_build) = &metadata.game_build {
+            if !self.quiet {
+                eprintln!("Game updated from build {} to {}", previous_build, build);
+            }
+
+            let (results, repaired_ids) = verify_after_game_update(
+                &game_path,
+                &metadata,
+                &self.settings.sources,
+                &build,
+                &self.settings.extraction_limits,
+                &self.settings.validation,
+                None,
+            )
+            .await;
+
+            for localization_id in &repaired_ids {
+                self.rebuild_dependents(&game_path, &metadata, localization_id);
+            }
+
+            for result in &results {
+                if let Some(error) = &result.error {
+                    eprintln!("warning: Failed to repair {}: {}", result.id, error);
+                } else if result.repaired && !self.quiet {
+                    eprintln!("Repaired {}", result.id);
+                }
+
+                if result.supported == Some(false) && !self.quiet {
+                    match &result.supported_update {
+                        Some(version) => eprintln!(
+                            "{} may not support this build, version {} does",
+                            result.id, version
+                        ),
+                        None => eprintln!("{} may not support this build", result.id),
+                    }
+                }
+            }
+        }
+
+        metadata.game_build = Some(build);
+        utils::save_installed_metadata(&game_path, &metadata)
+    }
+
+    fn test_launch(&self) -> anyhow::Result<()> {
+        let game_path = self.game_path().ok();
+        let commands = launch::launch_commands(&self.settings.launch, game_path.as_deref())?;
+
+        self.print(&commands, || {
+            commands
+                .iter()
+                .map(|command| command.display.clone())
+                .collect::<Vec<_>>()
+                .join("\n")
+        });
+        Ok(())
+    }
+
+    fn record_install(
+        &self,
+        game_path: &PathBuf,
+        metadata: &mut utils::InstalledMetadata,
+        localization: &utils::Localization,
+        source: &str,
+        report: utils::InstallReport,
+        components: Option<Vec<String>>,
+    ) -> anyhow::Result<()> {
+        let evicted = metadata.record_install(
+            localization,
+            source,
+            report.snapshot,
+            components,
+            steam::read_game_build(game_path),
+            self.settings.keep_versions,
+        );
+        utils::remove_localization_snapshots(game_path, &localization.id, &evicted);
+
+        self.rebuild_dependents(game_path, metadata, &localization.id);
+
+        utils::save_installed_metadata(game_path, metadata)
+    }
+
+    /// Reapplies overlays on top of `localization_id`, stacked ones after
+    /// their own base, and rebuilds the composites reading from it or from
+    /// one of those overlays.
+    fn rebuild_dependents(
+        &self,
+        game_path: &PathBuf,
+        metadata: &utils::InstalledMetadata,
+        localization_id: &str,
+    ) {
+        let overlays = utils::reapply_overlays(
+            game_path,
+            metadata,
+            localization_id,
+            &self.settings.extraction_limits,
+            &self.settings.validation,
+        );
+        for (overlay_id, e) in overlays {
+            eprintln!("warning: Failed to reapply overlay {}: {:#}", overlay_id, e);
+        }
+
+        let changed_ids: Vec<String> = std::iter::once(localization_id.to_string())
+            .chain(metadata.dependent_overlays(localization_id))
+            .collect();
+
+        let composites =
+            utils::rebuild_composites(game_path, metadata, &changed_ids, &self.settings.validation);
+        for (composite_id, e) in composites {
+            eprintln!(
+                "warning: Failed to rebuild composite {}: {:#}",
+                composite_id, e
+            );
+        }
+    }
+
+    fn report_issues(&self, localization_id: &str, issues: &[validation::ValidationIssue]) {
+        for issue in issues {
+            eprintln!("warning: {}: {}", localization_id, issue);
+        }
+    }
+
+    fn print_summary(&self, summary: &[UpdateResult]) {
+        self.print(&summary, || {
+            summary
+                .iter()
+                .map(|result| {
+                    let status = serde_json::to_value(&result.status)
+                        .ok()
+                        .and_then(|status| status.as_str().map(str::to_string))
+                        .unwrap_or_default();
+
+                    match &result.error {
+                        Some(error) => format!("{:<20} {} ({})", result.id, status, error),
+                        None => format!("{:<20} {}", result.id, status),
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        });
+    }
+}
+
+/// Steam launch option entry point: updates installed localizations within
+/// the configured timeout, then runs `command` in place of this process.
+/// Nothing that goes wrong before that stops the game from starting.
+pub fn wrap(command: &[String]) -> i32 {
+    if command.is_empty() {
+        eprintln!(
+            "error: No game command given, set the launch options to `{} %command%`",
+            WRAPPER_NAME
+        );
+        return 1;
+    }
+
+    match tokio::runtime::Runtime::new() {
+        Ok(runtime) => {
+            if let Err(e) = runtime.block_on(update_before_launch()) {
+                eprintln!("warning: Skipping localization updates: {:#}", e);
+            }
+            // Do not wait for a download the timeout gave up on
+            runtime.shutdown_background();
+        }
+        Err(e) => eprintln!("warning: Failed to start async runtime: {}", e),
+    }
+
+    run_game(command)
+}
+
+async fn update_before_launch() -> anyhow::Result<()> {
+    let settings = settings::load_headless_settings().context("Failed to load settings")?;
+    let timeout = Duration::from_secs(settings.wrapper.timeout_seconds);
+
+    // Extraction is synchronous and cannot be interrupted, so instead of a
+    // timeout around everything each step checks the deadline before it starts
+    let cli = Cli {
+        quiet: !settings.wrapper.progress,
+        settings,
+        json: false,
+        deadline: Some(Instant::now() + timeout),
+    };
+
+    if !cli.quiet {
+        eprintln!("Checking for localization updates...");
+    }
+
+    if let Err(e) = cli.within_deadline(cli.check_game_build()).await {
+        eprintln!("warning: Skipping game build check: {:#}", e);
+    }
+    cli.update().await?;
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn run_game(command: &[String]) -> i32 {
+    use std::os::unix::process::CommandExt;
+
+    // Only returns if the command could not be started
+    let e = Command::new(&command[0]).args(&command[1..]).exec();
+    eprintln!("error: Failed to run {}: {}", command[0], e);
+    127
+}
+
+#[cfg(not(unix))]
+fn run_game(command: &[String]) -> i32 {
+    match Command::new(&command[0]).args(&command[1..]).status() {
+        Ok(status) => status.code().unwrap_or(1),
+        Err(e) => {
+            eprintln!("error: Failed to run {}: {}", command[0], e);
+            127
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn recognizes_the_wrapper_name() {
+        assert!(is_wrapper("llm-wrapper"));
+        assert!(is_wrapper("/usr/local/bin/llm-wrapper"));
+        assert!(is_wrapper("llm-wrapper.exe"));
+        assert!(!is_wrapper("limbus-localization-manager"));
+        assert!(!is_wrapper("/opt/llm-wrapper/limbus-localization-manager"));
+        assert!(!is_wrapper("llm-wrapper-old"));
+    }
+
+    #[test]
+    fn wrapper_passes_every_argument_to_the_game() {
+        let command = args(&["/games/Limbus.exe", "install", "--json"]);
+
+        assert_eq!(
+            parse_invocation(Some("/usr/bin/llm-wrapper"), &command),
+            Invocation::Wrap(&command)
+        );
+    }
+
+    #[test]
+    fn dispatches_commands_and_falls_back_to_the_gui() {
+        let command = args(&["install", "en-plus", "--json"]);
+        assert_eq!(
+            parse_invocation(Some("limbus-localization-manager"), &command),
+            Invocation::Command(&command)
+        );
+
+        let json_first = args(&["--json", "list"]);
+        assert_eq!(
+            parse_invocation(Some("limbus-localization-manager"), &json_first),
+            Invocation::Command(&json_first)
+        );
+
+        let help = args(&["--help"]);
+        assert_eq!(
+            parse_invocation(Some("limbus-localization-manager"), &help),
+            Invocation::Command(&help)
+        );
+
+        assert_eq!(
+            parse_invocation(Some("limbus-localization-manager"), &[]),
+            Invocation::Gui
+        );
+        assert_eq!(
+            parse_invocation(None, &args(&["--minimized"])),
+            Invocation::Gui
+        );
+    }
+
+    #[test]
+    fn json_flag_is_accepted_anywhere() {
+        let command = args(&["--json", "install", "en-plus"]);
+        assert_eq!(
+            split_json_flag(&command),
+            (vec!["install", "en-plus"], true)
+        );
+
+        let command = args(&["list"]);
+        assert_eq!(split_json_flag(&command), (vec!["list"], false));
+    }
+
+    #[test]
+    fn wrap_without_a_command_fails() {
+        assert_eq!(wrap(&[]), 1);
+    }
+}
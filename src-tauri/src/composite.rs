@@ -0,0 +1,144 @@
+use crate::{coverage, overlay};
+use anyhow::Context;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+// Fallback id that stands for the English files shipped with the game
+pub const ENGLISH_FALLBACK: &str = "en";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CompositeLocalization {
+    pub id: String,             // Installed as Lang/<id>
+    pub name: String,           // Human readable name
+    pub primary: String,        // Localization whose text wins
+    pub fallbacks: Vec<String>, // Tried in order for missing files and keys
+}
+
+impl CompositeLocalization {
+    pub fn inputs(&self) -> impl Iterator<Item = &String> {
+        std::iter::once(&self.primary).chain(self.fallbacks.iter())
+    }
+
+    pub fn depends_on(&self, localization_id: &str) -> bool {
+        self.inputs().any(|id| id == localization_id)
+    }
+
+    /// Folders to read from, highest priority first.
+    pub fn source_directories(&self, game_path: &Path) -> Result<Vec<PathBuf>, anyhow::Error> {
+        self.inputs()
+            .map(|id| {
+                let path = if id == ENGLISH_FALLBACK {
+                    coverage::english_localization_directory(game_path)
+                } else {
+                    game_path.join("LimbusCompany_Data").join("Lang").join(id)
+                };
+
+                if !path.is_dir() {
+                    return Err(anyhow::anyhow!(
+                        "Input '{}' of composite localization '{}' is not installed",
+                        id,
+                        self.id
+                    ));
+                }
+
+                Ok(path)
+            })
+            .collect()
+    }
+}
+
+/// Writes the union of all `sources` into `target_dir`. JSON files found in
+/// several sources are merged key by key so the earlier source wins, any
+/// other file is taken from the first source that has it.
+pub fn build_composite(sources: &[PathBuf], target_dir: &Path) -> Result<(), anyhow::Error> {
+    let mut files: BTreeMap<String, Vec<(String, PathBuf)>> = BTreeMap::new();
+
+    for source in sources {
+        let mut pending = vec![source.clone()];
+
+        while let Some(current) = pending.pop() {
+            for entry in fs::read_dir(&current)
+                .with_context(|| format!("Failed to read directory {:?}", current))?
+            {
+                let entry =
+                    entry.with_context(|| format!("Error reading entry in {:?}", current))?;
+                let path = entry.path();
+
+                if path.is_dir() {
+                    pending.push(path);
+                    continue;
+                }
+
+                let relative = path.strip_prefix(source).unwrap_or(&path);
+                files
+                    .entry(coverage::file_key(relative))
+                    .or_default()
+                    .push((relative.to_string_lossy().replace('\\', "/"), path));
+            }
+        }
+    }
+
+    for candidates in files.values() {
+        let (relative, first) = &candidates[0];
+        let destination_path = target_dir.join(relative);
+
+        if let Some(parent) = destination_path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory {:?}", parent))?;
+        }
+
+        let is_json = first
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+
+        if is_json && candidates.len() > 1 {
+            debug!("Merging {} versions of {:?}", candidates.len(), relative);
+            merge_candidates(candidates, &destination_path)?;
+        } else {
+            debug!("Copying {:?} -> {:?}", first, destination_path);
+            fs::copy(first, &destination_path).with_context(|| {
+                format!("Failed to copy file {:?} to {:?}", first, destination_path)
+            })?;
+        }
+    }
+
+    Ok(())
+}
+
+fn merge_candidates(
+    candidates: &[(String, PathBuf)],
+    destination_path: &Path,
+) -> Result<(), anyhow::Error> {
+    let mut merged = None;
+
+    for (_, path) in candidates.iter().rev() {
+        let value = match overlay::read_json(path) {
+            Ok(value) => value,
+            Err(e) => {
+                warn!("Skipping unreadable fallback {:?}: {:#}", path, e);
+                continue;
+            }
+        };
+
+        match merged.as_mut() {
+            Some(merged) => overlay::merge_json(merged, value),
+            None => merged = Some(value),
+        }
+    }
+
+    let merged = merged.ok_or_else(|| {
+        anyhow::anyhow!("No readable version of {:?} was found", destination_path)
+    })?;
+
+    let content = serde_json::to_string_pretty(&merged)
+        .with_context(|| format!("Failed to serialize JSON file {:?}", destination_path))?;
+    fs::write(destination_path, content)
+        .with_context(|| format!("Failed to write JSON file {:?}", destination_path))?;
+
+    Ok(())
+}
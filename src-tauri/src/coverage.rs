@@ -0,0 +1,233 @@
+use anyhow::Context;
+use log::{debug, warn};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+#[derive(Serialize, Clone, Debug)]
+pub struct FileCoverage {
+    pub file: String,              // Relative to the English folder
+    pub keys_total: usize,         // Text keys in the English file
+    pub keys_translated: usize,    // Of those, present in the translation
+    pub missing_keys: Vec<String>, // In English but not in the translation
+    pub extra_keys: Vec<String>,   // In the translation but not in English
+    pub error: Option<String>,     // Translated file could not be parsed
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct CoverageReport {
+    pub localization_id: String,
+    pub files_total: usize,
+    pub missing_files: Vec<String>,
+    pub extra_files: Vec<String>,
+    pub keys_total: usize,
+    pub keys_translated: usize,
+    pub percentage: f64,
+    pub files: Vec<FileCoverage>, // Files present in both, with their key coverage
+}
+
+pub fn english_localization_directory(game_path: &Path) -> PathBuf {
+    game_path
+        .join("LimbusCompany_Data")
+        .join("Assets")
+        .join("Resources_moved")
+        .join("Localize")
+        .join("en")
+}
+
+/// Compares `Lang/<id>` with the English files shipped with the game, by
+/// file and by text key.
+pub fn localization_coverage(
+    game_path: &Path,
+    localization_id: &str,
+) -> Result<CoverageReport, anyhow::Error> {
+    let english_dir = english_localization_directory(game_path);
+    if !english_dir.is_dir() {
+        return Err(anyhow::anyhow!(
+            "English localization not found at {:?}",
+            english_dir
+        ));
+    }
+
+    let localization_dir = game_path
+        .join("LimbusCompany_Data")
+        .join("Lang")
+        .join(localization_id);
+    if !localization_dir.is_dir() {
+        return Err(anyhow::anyhow!(
+            "Localization '{}' is not installed",
+            localization_id
+        ));
+    }
+
+    let english_files = collect_json_files(&english_dir)?;
+    let mut translated_files = collect_json_files(&localization_dir)?;
+
+    let mut report = CoverageReport {
+        localization_id: localization_id.to_string(),
+        files_total: english_files.len(),
+        missing_files: Vec::new(),
+        extra_files: Vec::new(),
+        keys_total: 0,
+        keys_translated: 0,
+        percentage: 0.0,
+        files: Vec::new(),
+    };
+
+    for (name, english_path) in &english_files {
+        let english_keys = match read_json(english_path) {
+            Ok(value) => text_keys(&value),
+            Err(e) => {
+                warn!(
+                    "Skipping unreadable English file {:?}: {:#}",
+                    english_path, e
+                );
+                continue;
+            }
+        };
+        report.keys_total += english_keys.len();
+
+        let Some(translated_path) = translated_files.remove(name) else {
+            report
+                .missing_files
+                .push(relative_name(&english_dir, english_path));
+            continue;
+        };
+
+        let (translated_keys, error) = match read_json(&translated_path) {
+            Ok(value) => (text_keys(&value), None),
+            Err(e) => (BTreeSet::new(), Some(format!("{:#}", e))),
+        };
+
+        let missing_keys: Vec<String> =
+            english_keys.difference(&translated_keys).cloned().collect();
+        let extra_keys: Vec<String> = translated_keys.difference(&english_keys).cloned().collect();
+        let keys_translated = english_keys.len() - missing_keys.len();
+        report.keys_translated += keys_translated;
+
+        report.files.push(FileCoverage {
+            file: relative_name(&english_dir, english_path),
+            keys_total: english_keys.len(),
+            keys_translated,
+            missing_keys,
+            extra_keys,
+            error,
+        });
+    }
+
+    report.extra_files = translated_files
+        .values()
+        .map(|path| relative_name(&localization_dir, path))
+        .collect();
+
+    report.percentage = if report.keys_total == 0 {
+        100.0
+    } else {
+        report.keys_translated as f64 * 100.0 / report.keys_total as f64
+    };
+
+    debug!(
+        "Coverage of '{}': {}/{} keys ({:.1}%)",
+        localization_id, report.keys_translated, report.keys_total, report.percentage
+    );
+    Ok(report)
+}
+
+/// Game files carry a language prefix (`EN_Skills.json`, `KR_Skills.json`),
+/// so files are matched by their path without it.
+pub fn file_key(relative: &Path) -> String {
+    let mut key = relative.to_string_lossy().replace('\\', "/").to_lowercase();
+
+    let name_start = key.rfind('/').map_or(0, |i| i + 1);
+    let name = &key[name_start..];
+    if name.len() > 3 && name.as_bytes()[2] == b'_' && name[..2].chars().all(char::is_alphabetic) {
+        key.replace_range(name_start..name_start + 3, "");
+    }
+
+    key
+}
+
+fn relative_name(base: &Path, path: &Path) -> String {
+    path.strip_prefix(base)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+fn collect_json_files(dir: &Path) -> Result<BTreeMap<String, PathBuf>, anyhow::Error> {
+    let mut files = BTreeMap::new();
+    let mut pending = vec![dir.to_path_buf()];
+
+    while let Some(current) = pending.pop() {
+        for entry in fs::read_dir(&current)
+            .with_context(|| format!("Failed to read directory {:?}", current))?
+        {
+            let entry = entry.with_context(|| format!("Error reading entry in {:?}", current))?;
+            let path = entry.path();
+
+            if path.is_dir() {
+                pending.push(path);
+            } else if path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+            {
+                let relative = path.strip_prefix(dir).unwrap_or(&path);
+                files.insert(file_key(relative), path);
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+fn read_json(path: &Path) -> Result<Value, anyhow::Error> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read JSON file {:?}", path))?;
+
+    serde_json::from_str(content.trim_start_matches('\u{feff}'))
+        .with_context(|| format!("Failed to parse JSON file {:?}", path))
+}
+
+/// Paths of all string values, entries of `dataList` style arrays are
+/// addressed by their `id` so reordering does not count as a change.
+fn text_keys(value: &Value) -> BTreeSet<String> {
+    fn walk(value: &Value, path: String, keys: &mut BTreeSet<String>) {
+        match value {
+            Value::String(_) => {
+                keys.insert(path);
+            }
+            Value::Object(map) => {
+                for (key, value) in map {
+                    if key == "id" {
+                        continue;
+                    }
+                    let path = if path.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{}.{}", path, key)
+                    };
+                    walk(value, path, keys);
+                }
+            }
+            Value::Array(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    let segment = match item.get("id") {
+                        Some(Value::String(id)) => format!("[id={}]", id),
+                        Some(Value::Number(id)) => format!("[id={}]", id),
+                        _ => format!("[{}]", index),
+                    };
+                    walk(item, format!("{}{}", path, segment), keys);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut keys = BTreeSet::new();
+    walk(value, String::new(), &mut keys);
+    keys
+}
@@ -0,0 +1,255 @@
+use anyhow::Context;
+use log::{debug, error, info};
+use notify::{RecursiveMode, Watcher};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+use tokio::sync::{mpsc, Mutex};
+
+// Editors save in bursts, changes are synced once the folder is quiet
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct SyncReport {
+    pub id: String,
+    pub copied: Vec<String>,  // Relative paths written to Lang/<id>
+    pub removed: Vec<String>, // Relative paths deleted from Lang/<id>
+    pub errors: Vec<String>,
+}
+
+/// Keeps a folder mirrored into `Lang/<id>` until dropped.
+pub struct DevWatcher {
+    _watcher: notify::RecommendedWatcher,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl Drop for DevWatcher {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Swap files, backups and other leftovers editors write next to the file.
+fn is_temporary_file(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        return true;
+    };
+
+    let lowercase = name.to_lowercase();
+    name.starts_with('.')
+        || name.starts_with('~')
+        || name.starts_with('#')
+        || name.ends_with('~')
+        || [
+            ".tmp",
+            ".temp",
+            ".swp",
+            ".swx",
+            ".bak",
+            ".part",
+            ".crdownload",
+        ]
+        .iter()
+        .any(|suffix| lowercase.ends_with(suffix))
+}
+
+/// Makes `target_dir` an exact copy of `source_dir`, skipping temporary
+/// files on both sides.
+pub fn mirror_directory(
+    localization_id: &str,
+    source_dir: &Path,
+    target_dir: &Path,
+) -> Result<SyncReport, anyhow::Error> {
+    fs::create_dir_all(target_dir)
+        .with_context(|| format!("Failed to create directory {:?}", target_dir))?;
+
+    let source_files = list_files(source_dir)?;
+    let target_files = list_files(target_dir)?;
+
+    let changed: BTreeSet<PathBuf> = source_files.union(&target_files).cloned().collect();
+    Ok(sync_paths(
+        localization_id,
+        source_dir,
+        target_dir,
+        &changed,
+    ))
+}
+
+fn list_files(dir: &Path) -> Result<BTreeSet<PathBuf>, anyhow::Error> {
+    let mut files = BTreeSet::new();
+    let mut pending = vec![dir.to_path_buf()];
+
+    while let Some(current) = pending.pop() {
+        for entry in fs::read_dir(&current)
+            .with_context(|| format!("Failed to read directory {:?}", current))?
+        {
+            let entry = entry.with_context(|| format!("Error reading entry in {:?}", current))?;
+            let path = entry.path();
+
+            if is_temporary_file(&path) {
+                continue;
+            }
+
+            if path.is_dir() {
+                pending.push(path);
+            } else if let Ok(relative) = path.strip_prefix(dir) {
+                files.insert(relative.to_path_buf());
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+/// Copies or removes each changed path, relative to both folders.
+fn sync_paths(
+    localization_id: &str,
+    source_dir: &Path,
+    target_dir: &Path,
+    changed: &BTreeSet<PathBuf>,
+) -> SyncReport {
+    let mut report = SyncReport {
+        id: localization_id.to_string(),
+        ..Default::default()
+    };
+
+    for relative in changed {
+        let source_path = source_dir.join(relative);
+        let target_path = target_dir.join(relative);
+        let name = relative.to_string_lossy().replace('\\', "/");
+
+        let result = if source_path.is_file() {
+            copy_file(&source_path, &target_path).map(|_| report.copied.push(name.clone()))
+        } else if source_path.is_dir() {
+            // A folder was created or moved in, sync everything below it
+            match list_files(&source_path) {
+                Ok(files) => {
+                    let nested = files.into_iter().map(|file| relative.join(file)).collect();
+                    let nested_report =
+                        sync_paths(localization_id, source_dir, target_dir, &nested);
+                    report.copied.extend(nested_report.copied);
+                    report.removed.extend(nested_report.removed);
+                    report.errors.extend(nested_report.errors);
+                    Ok(())
+                }
+                Err(e) => Err(e),
+            }
+        } else if target_path.is_dir() {
+            fs::remove_dir_all(&target_path)
+                .with_context(|| format!("Failed to remove directory {:?}", target_path))
+                .map(|_| report.removed.push(name.clone()))
+        } else if target_path.is_file() {
+            fs::remove_file(&target_path)
+                .with_context(|| format!("Failed to remove file {:?}", target_path))
+                .map(|_| report.removed.push(name.clone()))
+        } else {
+            Ok(())
+        };
+
+        if let Err(e) = result {
+            error!("Failed to sync {:?}: {:?}", name, e);
+            report.errors.push(format!("{}: {:#}", name, e));
+        }
+    }
+
+    report
+}
+
+fn copy_file(source_path: &Path, target_path: &Path) -> Result<(), anyhow::Error> {
+    if let Some(parent) = target_path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory {:?}", parent))?;
+    }
+
+    debug!("Syncing {:?} -> {:?}", source_path, target_path);
+    fs::copy(source_path, target_path)
+        .with_context(|| format!("Failed to copy file {:?} to {:?}", source_path, target_path))?;
+
+    Ok(())
+}
+
+/// Watches `source_dir` and mirrors every change into `target_dir` while
+/// holding `lock`, calling `on_sync` after each debounced batch.
+pub fn watch<F>(
+    localization_id: &str,
+    source_dir: &Path,
+    target_dir: &Path,
+    lock: Arc<Mutex<()>>,
+    on_sync: F,
+) -> Result<DevWatcher, anyhow::Error>
+where
+    F: Fn(SyncReport) + Send + 'static,
+{
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+
+    let mut watcher = notify::recommended_watcher(move |event| {
+        let _ = sender.send(event);
+    })
+    .with_context(|| format!("Failed to create file watcher"))?;
+
+    watcher
+        .watch(source_dir, RecursiveMode::Recursive)
+        .with_context(|| format!("Failed to watch {:?}", source_dir))?;
+
+    let localization_id = localization_id.to_string();
+    let source_dir = source_dir.to_path_buf();
+    let target_dir = target_dir.to_path_buf();
+
+    let task = tokio::spawn(async move {
+        while let Some(event) = receiver.recv().await {
+            let mut changed = BTreeSet::new();
+            let mut next = Some(event);
+
+            while let Some(event) = next {
+                match event {
+                    Ok(event) => changed.extend(
+                        event
+                            .paths
+                            .iter()
+                            .filter_map(|path| path.strip_prefix(&source_dir).ok())
+                            .filter(|relative| !relative.as_os_str().is_empty())
+                            .filter(|relative| {
+                                !relative.components().any(|component| {
+                                    is_temporary_file(Path::new(component.as_os_str()))
+                                })
+                            })
+                            .map(Path::to_path_buf),
+                    ),
+                    Err(e) => error!("File watcher error: {:?}", e),
+                }
+
+                next = match tokio::time::timeout(DEBOUNCE, receiver.recv()).await {
+                    Ok(Some(event)) => Some(event),
+                    Ok(None) => return,
+                    Err(_) => None,
+                };
+            }
+
+            if changed.is_empty() {
+                continue;
+            }
+
+            let report = {
+                let _acquired_lock = lock.lock().await;
+                sync_paths(&localization_id, &source_dir, &target_dir, &changed)
+            };
+            info!(
+                "Synced '{}': {} copied, {} removed",
+                localization_id,
+                report.copied.len(),
+                report.removed.len()
+            );
+            on_sync(report);
+        }
+    });
+
+    Ok(DevWatcher {
+        _watcher: watcher,
+        task,
+    })
+}
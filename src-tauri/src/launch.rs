@@ -0,0 +1,260 @@
+use crate::steam;
+use anyhow::Context;
+use log::{debug, info};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::process::Command;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LaunchMethod {
+    #[default]
+    SteamUrl, // steam://run/<app id>, opened by the system
+    SteamApplaunch, // steam -applaunch <app id>
+    Custom,         // `command` template
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct LaunchSettings {
+    pub method: LaunchMethod,
+    pub command: String, // Custom method only, e.g. "gamescope -f -- {game_exe} {args}"
+    pub arguments: Vec<String>, // Passed to the game
+    pub environment: HashMap<String, String>, // Set for the launched process
+}
+
+/// One process to spawn, exactly as it will be run.
+#[derive(Serialize, Clone, Debug)]
+pub struct LaunchCommand {
+    pub program: String,
+    pub args: Vec<String>,
+    pub environment: BTreeMap<String, String>,
+    pub display: String, // Shell-like rendering for the UI
+}
+
+impl LaunchCommand {
+    fn new(program: String, args: Vec<String>, settings: &LaunchSettings) -> Self {
+        let environment: BTreeMap<String, String> = settings
+            .environment
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+
+        let display = environment
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, quote(value)))
+            .chain(std::iter::once(quote(&program)))
+            .chain(args.iter().map(|arg| quote(arg)))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        Self {
+            program,
+            args,
+            environment,
+            display,
+        }
+    }
+
+    fn spawn(&self) -> Result<(), anyhow::Error> {
+        debug!("Spawning {}", self.display);
+
+        Command::new(&self.program)
+            .args(&self.args)
+            .envs(&self.environment)
+            .spawn()
+            .with_context(|| format!("Failed to run {}", self.program))?;
+
+        Ok(())
+    }
+}
+
+fn quote(value: &str) -> String {
+    let is_plain = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_alphanumeric() || "-_./:=+,%@\\".contains(c));
+
+    if is_plain {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
+
+/// Splits a command template into words. Quotes group words with spaces,
+/// a backslash escapes a quote or a space.
+fn split_words(template: &str) -> Result<Vec<String>, anyhow::Error> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut quote_char = None;
+    let mut chars = template.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (quote_char, c) {
+            (Some(q), c) if c == q => quote_char = None,
+            (Some(_), c) => current.push(c),
+            (None, '"' | '\'') => {
+                quote_char = Some(c);
+                in_word = true;
+            }
+            (None, '\\') if chars.peek().is_some_and(|next| "\"' \\".contains(*next)) => {
+                current.extend(chars.next());
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                current.push(c);
+                in_word = true;
+            }
+        }
+    }
+
+    if quote_char.is_some() {
+        return Err(anyhow::anyhow!("Unterminated quote in launch command"));
+    }
+    if in_word {
+        words.push(current);
+    }
+
+    Ok(words)
+}
+
+fn steam_url(settings: &LaunchSettings) -> String {
+    if settings.arguments.is_empty() {
+        return format!("steam://run/{}", steam::LIMBUS_STEAM_ID);
+    }
+
+    format!(
+        "steam://run/{}//{}/",
+        steam::LIMBUS_STEAM_ID,
+        settings.arguments.join(" ").replace(' ', "%20")
+    )
+}
+
+#[cfg(target_os = "windows")]
+fn steam_url_commands(settings: &LaunchSettings) -> Vec<LaunchCommand> {
+    let args = vec!["/C".to_string(), "start".to_string(), steam_url(settings)];
+    vec![LaunchCommand::new("cmd".to_string(), args, settings)]
+}
+
+#[cfg(target_os = "macos")]
+fn steam_url_commands(settings: &LaunchSettings) -> Vec<LaunchCommand> {
+    vec![LaunchCommand::new(
+        "open".to_string(),
+        vec![steam_url(settings)],
+        settings,
+    )]
+}
+
+/// The URL handler may belong to another Steam than the one with the
+/// game, so Flatpak and Snap installs are started directly first.
+#[cfg(target_os = "linux")]
+fn steam_url_commands(settings: &LaunchSettings) -> Vec<LaunchCommand> {
+    let launcher = steam::game_steam_install()
+        .map(|install| install.launcher())
+        .filter(|launcher| launcher[0] != "steam");
+
+    launcher
+        .into_iter()
+        .chain([vec!["xdg-open".to_string()], vec!["steam".to_string()]])
+        .map(|mut words| {
+            words.push(steam_url(settings));
+            let program = words.remove(0);
+            LaunchCommand::new(program, words, settings)
+        })
+        .collect()
+}
+
+/// Steam executable and the arguments that come before Steam's own.
+fn steam_launcher() -> Vec<String> {
+    steam::game_steam_install()
+        .map(|install| install.launcher())
+        .unwrap_or_else(|| vec!["steam".to_string()])
+}
+
+/// Commands for the configured launch method. The Steam URL method has
+/// fallbacks on some platforms, they are tried in order.
+pub fn launch_commands(
+    settings: &LaunchSettings,
+    game_path: Option<&Path>,
+) -> Result<Vec<LaunchCommand>, anyhow::Error> {
+    match settings.method {
+        LaunchMethod::SteamUrl => Ok(steam_url_commands(settings)),
+        LaunchMethod::SteamApplaunch => {
+            let mut words = steam_launcher();
+            words.extend(["-applaunch".to_string(), steam::LIMBUS_STEAM_ID.to_string()]);
+            words.extend(settings.arguments.iter().cloned());
+
+            let program = words.remove(0);
+            Ok(vec![LaunchCommand::new(program, words, settings)])
+        }
+        LaunchMethod::Custom => {
+            let game_dir = game_path.ok_or_else(|| {
+                anyhow::anyhow!("Game directory is required for a custom command")
+            })?;
+            let game_exe = game_dir.join("LimbusCompany.exe");
+
+            let mut has_args_placeholder = false;
+            let mut words = Vec::new();
+
+            for word in split_words(&settings.command)? {
+                if word == "{args}" {
+                    has_args_placeholder = true;
+                    words.extend(settings.arguments.iter().cloned());
+                    continue;
+                }
+
+                words.push(
+                    word.replace("{game_dir}", &game_dir.to_string_lossy())
+                        .replace("{game_exe}", &game_exe.to_string_lossy())
+                        .replace("{app_id}", &steam::LIMBUS_STEAM_ID.to_string()),
+                );
+            }
+
+            if !has_args_placeholder {
+                words.extend(settings.arguments.iter().cloned());
+            }
+
+            if words.is_empty() {
+                return Err(anyhow::anyhow!("Custom launch command is empty"));
+            }
+
+            let program = words.remove(0);
+            Ok(vec![LaunchCommand::new(program, words, settings)])
+        }
+    }
+}
+
+pub fn launch_game(
+    settings: &LaunchSettings,
+    game_path: Option<&Path>,
+) -> Result<(), anyhow::Error> {
+    let commands = launch_commands(settings, game_path)?;
+
+    let mut last_error = None;
+    for command in &commands {
+        match command.spawn() {
+            Ok(()) => {
+                info!("Launched game with {}", command.display);
+                return Ok(());
+            }
+            Err(e) => {
+                debug!("Launch attempt failed: {:?}", e);
+                last_error = Some(e);
+            }
+        }
+    }
+
+    Err(match (settings.method, last_error) {
+        (LaunchMethod::Custom, Some(e)) => e,
+        _ => anyhow::anyhow!("Failed to launch Steam. Is it installed?"),
+    })
+}
@@ -1,17 +1,30 @@
+mod archive;
+pub mod cli;
+mod composite;
+mod coverage;
+mod devmode;
+mod launch;
+mod overlay;
 mod settings;
 mod steam;
 mod utils;
+mod validation;
+mod vdf;
 
 use dashmap::DashMap;
 use log::{debug, error, info};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
 use tauri::{Emitter, Manager, State};
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, OwnedMutexGuard};
 
 #[derive(Clone, Serialize, Deserialize)]
 struct AppState {
     settings: settings::AppSettings,
     installed_metadata: Option<utils::InstalledMetadata>,
+    #[serde(default)]
+    active_localization: Option<String>, // Language set in Lang/config.json
 }
 
 impl AppState {
@@ -22,6 +35,7 @@ impl AppState {
                 settings::AppSettings::default()
             }),
             installed_metadata: None,
+            active_localization: None,
         };
 
         app_state.load_installed_metadata().unwrap_or_else(|e| {
@@ -68,15 +82,45 @@ impl AppState {
 
         self.installed_metadata = Some(installed_metadata);
         self.settings.game_directory = game_directory.clone();
+        self.load_active_localization();
         Ok(())
     }
 
     fn load_installed_metadata(&mut self) -> anyhow::Result<()> {
         let game_path = self.game_path()?;
         self.installed_metadata = Some(utils::load_installed_metadata(&game_path)?);
+        self.load_active_localization();
         Ok(())
     }
 
+    fn load_active_localization(&mut self) {
+        self.active_localization = self
+            .game_path()
+            .and_then(|game_path| utils::get_active_localization(&game_path))
+            .unwrap_or_else(|e| {
+                debug!("Failed to read active localization: {:?}", e);
+                None
+            });
+    }
+
+    fn activate_after_install(&mut self, game_path: &std::path::PathBuf, localization_id: &str) {
+        if !self.settings.activate_after_install {
+            return;
+        }
+
+        match utils::set_active_localization(game_path, &self.current_metadata(), localization_id) {
+            Ok(()) => self.active_localization = Some(localization_id.to_string()),
+            Err(e) => error!("Failed to activate localization: {:?}", e),
+        }
+    }
+
+    /// Copy handed to installs, overlays check their base version in it.
+    fn current_metadata(&self) -> utils::InstalledMetadata {
+        self.installed_metadata
+            .clone()
+            .unwrap_or_else(utils::InstalledMetadata::new)
+    }
+
     fn save_installed_metadata(&self) -> anyhow::Result<()> {
         let game_path = self.game_path()?;
 
@@ -94,9 +138,297 @@ struct RemoteLocalizations {
     localizations: Vec<utils::Localization>,
 }
 
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum UpdateStatus {
+    UpToDate,
+    Updated,
+    Pinned,
+    Removed,
+    Local,
+    DevMode,
+    Unknown,
+    Failed,
+}
+
+#[derive(Clone, Serialize)]
+struct UpdateResult {
+    id: String,
+    status: UpdateStatus,
+    error: Option<String>,
+}
+
+impl UpdateResult {
+    fn new(id: &str, status: UpdateStatus) -> Self {
+        Self {
+            id: id.to_string(),
+            status,
+            error: None,
+        }
+    }
+
+    fn failed(id: &str, error: &anyhow::Error) -> Self {
+        Self {
+            id: id.to_string(),
+            status: UpdateStatus::Failed,
+            error: Some(format!("{:#}", error)),
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+struct ValidationReport<'a> {
+    id: &'a str,
+    issues: &'a [validation::ValidationIssue],
+}
+
+fn emit_validation_issues(
+    app_handle: &tauri::AppHandle,
+    localization_id: &str,
+    issues: &[validation::ValidationIssue],
+) {
+    if issues.is_empty() {
+        return;
+    }
+
+    let _ = app_handle.emit(
+        "validation_issues",
+        ValidationReport {
+            id: localization_id,
+            issues,
+        },
+    );
+}
+
+#[derive(Clone, Serialize)]
+struct AvailableUpdate {
+    id: String,
+    source: String,
+    installed_version: String,
+    version: String,
+    size: u64,
+    #[serde(skip)]
+    localization: utils::Localization,
+    #[serde(skip)]
+    components: Option<Vec<String>>,
+}
+
 type AppStateMutex = Mutex<AppState>;
 type RemoteLocalizationsMutex = Mutex<Option<RemoteLocalizations>>;
-type LocalizationLocks = DashMap<(String, std::path::PathBuf), Mutex<()>>;
+type LocalizationLocks = DashMap<(String, std::path::PathBuf), Arc<Mutex<()>>>;
+type DevWatchers = DashMap<String, devmode::DevWatcher>;
+type PendingActionsMutex = Mutex<Vec<PendingAction>>;
+type GameRunning = std::sync::atomic::AtomicBool; // Kept up to date by the game monitor
+
+// How often the game monitor looks at the process list
+const GAME_MONITOR_INTERVAL: std::time::Duration = std::time::Duration::from_secs(3);
+
+/// Work requested while the game was running, done once it exits.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum PendingAction {
+    Install {
+        localization: Box<utils::Localization>,
+        packs: Option<Vec<String>>,
+        components: Option<Vec<String>>,
+    },
+    Update,
+}
+
+/// Serializes work on `Lang/<id>` of one game directory.
+fn localization_lock_entry(
+    localization_lock: &LocalizationLocks,
+    game_path: &std::path::Path,
+    localization_id: &str,
+) -> Arc<Mutex<()>> {
+    localization_lock
+        .entry((localization_id.to_string(), game_path.to_path_buf()))
+        .or_insert_with(|| Arc::new(Mutex::new(())))
+        .clone()
+}
+
+async fn lock_localization(
+    localization_lock: &LocalizationLocks,
+    game_path: &std::path::Path,
+    localization_id: &str,
+) -> OwnedMutexGuard<()> {
+    localization_lock_entry(localization_lock, game_path, localization_id)
+        .lock_owned()
+        .await
+}
+
+/// Mirrors `source_path` into `Lang/<id>` and keeps watching it for changes.
+async fn start_dev_watcher(
+    app_handle: &tauri::AppHandle,
+    game_path: &std::path::Path,
+    localization_id: &str,
+    source_path: &std::path::Path,
+) -> anyhow::Result<devmode::DevWatcher> {
+    let localization_lock = app_handle.state::<LocalizationLocks>();
+    let target_path = game_path
+        .join("LimbusCompany_Data")
+        .join("Lang")
+        .join(localization_id);
+
+    {
+        let _acquired_lock =
+            lock_localization(&localization_lock, game_path, localization_id).await;
+
+        let report = devmode::mirror_directory(localization_id, source_path, &target_path)?;
+        let _ = app_handle.emit("dev_mode:synced", report);
+    }
+
+    let sync_handle = app_handle.clone();
+    devmode::watch(
+        localization_id,
+        source_path,
+        &target_path,
+        localization_lock_entry(&localization_lock, game_path, localization_id),
+        move |report| {
+            let _ = sync_handle.emit("dev_mode:synced", report);
+        },
+    )
+}
+
+/// Resumes dev mode for folders that were still mirrored when the app closed,
+/// dropping the ones whose folder is gone.
+async fn resume_dev_mode(app_handle: &tauri::AppHandle) -> anyhow::Result<()> {
+    let state = app_handle.state::<AppStateMutex>();
+    let dev_watchers = app_handle.state::<DevWatchers>();
+
+    let (game_path, dev_folders) = {
+        let app_state_guard = state.lock().await;
+        let game_path = app_state_guard.game_path()?;
+        let dev_folders: Vec<(String, String)> = app_state_guard
+            .installed_metadata
+            .iter()
+            .flat_map(|metadata| metadata.installed.values())
+            .filter_map(|installed| {
+                installed
+                    .dev_folder
+                    .clone()
+                    .map(|folder| (installed.id.clone(), folder))
+            })
+            .collect();
+        (game_path, dev_folders)
+    };
+
+    let mut stopped = Vec::new();
+    for (localization_id, folder) in dev_folders {
+        let source_path = std::path::PathBuf::from(&folder);
+        let watcher = if source_path.is_dir() {
+            start_dev_watcher(app_handle, &game_path, &localization_id, &source_path).await
+        } else {
+            Err(anyhow::anyhow!("Folder {:?} does not exist", folder))
+        };
+
+        match watcher {
+            Ok(watcher) => {
+                info!("Resumed dev mode for '{}'", localization_id);
+                dev_watchers.insert(localization_id, watcher);
+            }
+            Err(e) => {
+                error!(
+                    "Failed to resume dev mode for '{}': {:?}",
+                    localization_id, e
+                );
+                stopped.push(localization_id);
+            }
+        }
+    }
+
+    if stopped.is_empty() {
+        return Ok(());
+    }
+
+    let mut app_state_guard = state.lock().await;
+    if let Some(metadata) = app_state_guard.installed_metadata.as_mut() {
+        for localization_id in &stopped {
+            if let Some(installed) = metadata.installed.get_mut(localization_id) {
+                installed.dev_folder = None;
+            }
+        }
+    }
+    app_state_guard.save_installed_metadata()?;
+    let _ = app_handle.emit("app_state_updated", app_state_guard.clone());
+
+    Ok(())
+}
+
+/// Locks several ids in sorted order, so two installs of overlapping packs
+/// cannot wait on each other.
+async fn lock_localizations(
+    localization_lock: &LocalizationLocks,
+    game_path: &std::path::Path,
+    localization_ids: &[String],
+) -> Vec<OwnedMutexGuard<()>> {
+    let mut localization_ids = localization_ids.to_vec();
+    localization_ids.sort();
+    localization_ids.dedup();
+
+    let mut guards = Vec::new();
+    for localization_id in &localization_ids {
+        guards.push(lock_localization(localization_lock, game_path, localization_id).await);
+    }
+    guards
+}
+
+fn game_running(app_handle: &tauri::AppHandle) -> bool {
+    app_handle
+        .state::<GameRunning>()
+        .load(std::sync::atomic::Ordering::Relaxed)
+}
+
+/// Reapplies overlays on top of `base_id`, stacked ones after their own base,
+/// and rebuilds composites reading from it or from one of those overlays.
+async fn rebuild_dependents(
+    app_handle: &tauri::AppHandle,
+    state: &AppStateMutex,
+    game_path: &std::path::PathBuf,
+    base_id: &str,
+) {
+    let (installed_metadata, extraction_limits, validation) = {
+        let app_state_guard = state.lock().await;
+        (
+            app_state_guard.installed_metadata.clone(),
+            app_state_guard.settings.extraction_limits.clone(),
+            app_state_guard.settings.validation.clone(),
+        )
+    };
+
+    let Some(installed_metadata) = installed_metadata else {
+        return;
+    };
+
+    let localization_lock = app_handle.state::<LocalizationLocks>();
+    let overlay_ids = installed_metadata.dependent_overlays(base_id);
+
+    for overlay_id in &overlay_ids {
+        let _acquired_lock = lock_localization(&localization_lock, game_path, overlay_id).await;
+
+        if let Err(e) = utils::reapply_overlay(
+            game_path,
+            &installed_metadata,
+            overlay_id,
+            &extraction_limits,
+            &validation,
+        ) {
+            error!("Failed to reapply overlay {}: {:?}", overlay_id, e);
+            let _ = app_handle.emit("overlay_failed", UpdateResult::failed(overlay_id, &e));
+        }
+    }
+
+    let changed_ids: Vec<String> = std::iter::once(base_id.to_string())
+        .chain(overlay_ids)
+        .collect();
+
+    for (composite_id, e) in
+        utils::rebuild_composites(game_path, &installed_metadata, &changed_ids, &validation)
+    {
+        error!("Failed to rebuild composite {}: {:?}", composite_id, e);
+        let _ = app_handle.emit("composite_failed", UpdateResult::failed(&composite_id, &e));
+    }
+}
 
 #[tauri::command]
 async fn get_latest_version() -> Result<String, String> {
@@ -157,7 +489,8 @@ async fn get_available_localizations(
 
 #[tauri::command]
 async fn get_app_state(state: State<'_, AppStateMutex>) -> Result<AppState, String> {
-    let app_state_guard = state.lock().await;
+    let mut app_state_guard = state.lock().await;
+    app_state_guard.load_active_localization();
     Ok(app_state_guard.clone())
 }
 
@@ -200,18 +533,43 @@ async fn install_localization(
     state: State<'_, AppStateMutex>,
     localization_lock: State<'_, LocalizationLocks>,
     localization: utils::Localization,
+    packs: Option<Vec<String>>,
+    components: Option<Vec<String>>,
 ) -> Result<(), String> {
-    debug!("Installing localization: {:?}", localization.id);
+    debug!(
+        "Installing localization: {:?}, packs: {:?}, components: {:?}",
+        localization.id, packs, components
+    );
 
-    if steam::is_game_running() {
+    if game_running(&app_handle) {
         return Err("Game is running".to_string());
     }
 
+    let targets: Vec<(utils::Localization, Option<Vec<String>>)> = localization
+        .selected_packs(packs.as_deref())
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|pack| (pack, components.clone()))
+        .collect();
+    let target_ids: Vec<String> = targets.iter().map(|(pack, _)| pack.id.clone()).collect();
+
     let game_path;
     let source;
+    let extraction_limits;
+    let validation;
 
     {
         let app_state_guard = state.lock().await;
+        extraction_limits = app_state_guard.settings.extraction_limits.clone();
+        validation = app_state_guard.settings.validation.clone();
+
+        if app_state_guard
+            .installed_metadata
+            .as_ref()
+            .is_some_and(|metadata| target_ids.iter().any(|id| metadata.is_dev_folder(id)))
+        {
+            return Err("Localization is in dev mode".to_string());
+        }
 
         source = app_state_guard
             .settings
@@ -225,17 +583,153 @@ async fn install_localization(
         })?;
     }
 
-    let lock = localization_lock
-        .entry((localization.id.clone(), game_path.clone()))
-        .or_insert_with(|| Mutex::new(()));
-    let _acquired_lock = lock.lock().await;
+    // Updates lock sub-packs one by one, so the same keys are taken here
+    let acquired_locks = lock_localizations(&localization_lock, &game_path, &target_ids).await;
+
+    let installed_metadata = state.lock().await.current_metadata();
+    let results = utils::install_shared_archive(
+        &game_path,
+        &installed_metadata,
+        &targets,
+        &extraction_limits,
+        &validation,
+    )
+    .await
+    .map_err(|e| {
+        error!("Failed to install localization: {:?}", e);
+        e.to_string()
+    })?;
+
+    let mut installed = Vec::new();
+    let mut errors = Vec::new();
 
-    utils::install_localization(&game_path, &localization)
-        .await
-        .map_err(|e| {
-            error!("Failed to install localization: {:?}", e);
+    for result in results {
+        match result {
+            Ok(report) => {
+                emit_validation_issues(
+                    &app_handle,
+                    &report.snapshot.localization.id,
+                    &report.issues,
+                );
+                installed.push(report);
+            }
+            Err(e) => {
+                error!("Failed to install localization: {:?}", e);
+                errors.push(format!("{:#}", e));
+            }
+        }
+    }
+
+    let installed_ids: Vec<String> = installed
+        .iter()
+        .map(|report| report.snapshot.localization.id.clone())
+        .collect();
+
+    {
+        let mut app_state_guard = state.lock().await;
+        let keep_versions = app_state_guard.settings.keep_versions;
+
+        let installed_metadata = app_state_guard
+            .installed_metadata
+            .get_or_insert_with(utils::InstalledMetadata::new);
+
+        for report in installed {
+            let pack = report.snapshot.localization.clone();
+            let evicted = installed_metadata.record_install(
+                &pack,
+                &source,
+                report.snapshot,
+                components.clone(),
+                steam::read_game_build(&game_path),
+                keep_versions,
+            );
+            utils::remove_localization_snapshots(&game_path, &pack.id, &evicted);
+        }
+
+        if let Some(id) = installed_ids.first() {
+            app_state_guard.activate_after_install(&game_path, id);
+        }
+
+        app_state_guard.save_installed_metadata().map_err(|e| {
+            error!("Failed to save installed metadata: {:?}", e);
+            e.to_string()
+        })?;
+
+        app_handle
+            .emit("app_state_updated", app_state_guard.clone())
+            .map_err(|e| {
+                error!("Failed to emit app state updated: {:?}", e);
+                e.to_string()
+            })?;
+    }
+
+    // Overlays take their own locks, one may be among the installed packs
+    drop(acquired_locks);
+    for id in &installed_ids {
+        rebuild_dependents(&app_handle, &state, &game_path, id).await;
+    }
+
+    if !errors.is_empty() {
+        return Err(errors.join("; "));
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+async fn install_local_localization(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppStateMutex>,
+    localization_lock: State<'_, LocalizationLocks>,
+    path: String,
+    id: String,
+    name: String,
+    format: utils::Format,
+) -> Result<(), String> {
+    debug!("Installing local localization {:?} from {:?}", id, path);
+
+    if game_running(&app_handle) {
+        return Err("Game is running".to_string());
+    }
+
+    if id.is_empty() || id.starts_with('.') || id.contains(['/', '\\']) {
+        return Err("Invalid localization id".to_string());
+    }
+
+    let source_path = std::path::PathBuf::from(&path);
+    let localization = utils::Localization::local(&id, &name, format, &source_path);
+
+    let game_path;
+    let extraction_limits;
+    let validation;
+
+    {
+        let app_state_guard = state.lock().await;
+        extraction_limits = app_state_guard.settings.extraction_limits.clone();
+        validation = app_state_guard.settings.validation.clone();
+
+        game_path = app_state_guard.game_path().map_err(|e| {
+            error!("Failed to get game directory: {:?}", e);
             e.to_string()
         })?;
+    }
+
+    let _acquired_lock = lock_localization(&localization_lock, &game_path, &localization.id).await;
+
+    let installed_metadata = state.lock().await.current_metadata();
+    let report = utils::install_local_localization(
+        &game_path,
+        &installed_metadata,
+        &source_path,
+        &localization,
+        &extraction_limits,
+        &validation,
+    )
+    .map_err(|e| {
+        error!("Failed to install local localization: {:?}", e);
+        e.to_string()
+    })?;
+    emit_validation_issues(&app_handle, &localization.id, &report.issues);
 
     utils::install_fonts_for_localization(&game_path, &localization)
         .await
@@ -246,24 +740,21 @@ async fn install_localization(
 
     {
         let mut app_state_guard = state.lock().await;
-
-        match app_state_guard.installed_metadata {
-            Some(ref mut installed_metadata) => {
-                installed_metadata.installed.insert(
-                    localization.id.clone(),
-                    utils::InstalledLocalization {
-                        id: localization.id.clone(),
-                        version: localization.version.clone(),
-                        source,
-                    },
-                );
-            }
-            None => {
-                app_state_guard.installed_metadata = Some(
-                    utils::InstalledMetadata::with_localization(&localization, &source),
-                );
-            }
-        }
+        let keep_versions = app_state_guard.settings.keep_versions;
+
+        let evicted = app_state_guard
+            .installed_metadata
+            .get_or_insert_with(utils::InstalledMetadata::new)
+            .record_install(
+                &localization,
+                utils::LOCAL_SOURCE,
+                report.snapshot,
+                None,
+                steam::read_game_build(&game_path),
+                keep_versions,
+            );
+        utils::remove_localization_snapshots(&game_path, &localization.id, &evicted);
+        app_state_guard.activate_after_install(&game_path, &localization.id);
 
         app_state_guard.save_installed_metadata().map_err(|e| {
             error!("Failed to save installed metadata: {:?}", e);
@@ -278,6 +769,8 @@ async fn install_localization(
             })?;
     }
 
+    rebuild_dependents(&app_handle, &state, &game_path, &localization.id).await;
+
     Ok(())
 }
 
@@ -286,11 +779,14 @@ async fn uninstall_localization(
     app_handle: tauri::AppHandle,
     state: State<'_, AppStateMutex>,
     localization_lock: State<'_, LocalizationLocks>,
+    dev_watchers: State<'_, DevWatchers>,
     localization: utils::Localization,
 ) -> Result<(), String> {
     debug!("Uninstalling localization: {:?}", localization.id);
 
-    if steam::is_game_running() {
+    dev_watchers.remove(&localization.id);
+
+    if game_running(&app_handle) {
         return Err("Game is running".to_string());
     }
 
@@ -305,10 +801,7 @@ async fn uninstall_localization(
         })?;
     }
 
-    let lock = localization_lock
-        .entry((localization.id.clone(), game_path.clone()))
-        .or_insert_with(|| Mutex::new(()));
-    let _acquired_lock = lock.lock().await;
+    let _acquired_lock = lock_localization(&localization_lock, &game_path, &localization.id).await;
 
     utils::uninstall_localization(&game_path, &localization)
         .await
@@ -317,11 +810,35 @@ async fn uninstall_localization(
             e.to_string()
         })?;
 
+    // Overlays cannot be rebuilt without their base, they are removed with it
+    let overlays = state
+        .lock()
+        .await
+        .current_metadata()
+        .dependent_overlay_localizations(&localization.id);
+    let mut removed_ids = vec![localization.id.clone()];
+
+    for overlay in overlays {
+        let _acquired_lock = lock_localization(&localization_lock, &game_path, &overlay.id).await;
+
+        info!(
+            "Removing overlay {} of uninstalled {}",
+            overlay.id, localization.id
+        );
+        match utils::uninstall_localization(&game_path, &overlay).await {
+            Ok(()) => removed_ids.push(overlay.id),
+            Err(e) => error!("Failed to uninstall overlay {}: {:?}", overlay.id, e),
+        }
+    }
+
     {
         let mut app_state_guard = state.lock().await;
 
         if let Some(ref mut installed_metadata) = app_state_guard.installed_metadata {
-            installed_metadata.installed.remove(&localization.id);
+            for id in &removed_ids {
+                installed_metadata.installed.remove(id);
+                installed_metadata.stashed.remove(id);
+            }
         }
 
         app_state_guard.save_installed_metadata().map_err(|e| {
@@ -349,29 +866,179 @@ async fn repair_localization(
 ) -> Result<(), String> {
     debug!("Repairing localization: {:?}", localization.id);
 
-    install_localization(app_handle, state, localization_lock, localization).await?;
+    let (packs, components) = {
+        let app_state_guard = state.lock().await;
+        let installed = app_state_guard
+            .installed_metadata
+            .as_ref()
+            .map(|metadata| &metadata.installed);
+
+        let packs: Vec<String> = localization
+            .packs
+            .iter()
+            .filter(|pack| installed.is_some_and(|installed| installed.contains_key(&pack.id)))
+            .map(|pack| pack.id.clone())
+            .collect();
+
+        let components = installed
+            .and_then(|installed| {
+                installed
+                    .get(&localization.id)
+                    .or_else(|| packs.first().and_then(|id| installed.get(id)))
+            })
+            .and_then(|installed| installed.components.clone());
+
+        ((!packs.is_empty()).then_some(packs), components)
+    };
+
+    install_localization(
+        app_handle,
+        state,
+        localization_lock,
+        localization,
+        packs,
+        components,
+    )
+    .await?;
     Ok(())
 }
 
 #[tauri::command]
-async fn set_game_directory(
+async fn rollback_localization(
     app_handle: tauri::AppHandle,
     state: State<'_, AppStateMutex>,
-    directory: Option<String>,
+    localization_lock: State<'_, LocalizationLocks>,
+    localization_id: String,
+    version: String,
 ) -> Result<(), String> {
-    debug!("Setting game directory to: {:?}", directory);
+    debug!(
+        "Rolling back localization {:?} to version {:?}",
+        localization_id, version
+    );
 
-    let mut app_state_guard = state.lock().await;
+    if game_running(&app_handle) {
+        return Err("Game is running".to_string());
+    }
 
-    app_state_guard
-        .update_game_directory(&directory)
+    let game_path;
+    let source;
+    let snapshot;
+    let components;
+    let extraction_limits;
+    let validation;
+
+    {
+        let app_state_guard = state.lock().await;
+        extraction_limits = app_state_guard.settings.extraction_limits.clone();
+        validation = app_state_guard.settings.validation.clone();
+
+        game_path = app_state_guard.game_path().map_err(|e| {
+            error!("Failed to get game directory: {:?}", e);
+            e.to_string()
+        })?;
+
+        let installed = app_state_guard
+            .installed_metadata
+            .as_ref()
+            .and_then(|metadata| metadata.installed.get(&localization_id))
+            .ok_or_else(|| "Localization is not installed".to_string())?;
+
+        source = installed.source.clone();
+        components = installed.components.clone();
+        snapshot = installed
+            .snapshots
+            .iter()
+            .find(|snapshot| snapshot.version == version)
+            .cloned()
+            .ok_or_else(|| "Version is not available for rollback".to_string())?;
+    }
+
+    let _acquired_lock = lock_localization(&localization_lock, &game_path, &localization_id).await;
+
+    let installed_metadata = state.lock().await.current_metadata();
+    let issues = utils::restore_localization_snapshot(
+        &game_path,
+        &installed_metadata,
+        &snapshot,
+        components.as_deref(),
+        &extraction_limits,
+        &validation,
+    )
+    .map_err(|e| {
+        error!("Failed to restore localization snapshot: {:?}", e);
+        e.to_string()
+    })?;
+    emit_validation_issues(&app_handle, &localization_id, &issues);
+
+    utils::install_fonts_for_localization(&game_path, &snapshot.localization)
+        .await
         .map_err(|e| {
-            error!("Failed to update game directory: {:?}", e);
+            error!("Failed to install fonts for localization: {:?}", e);
             e.to_string()
         })?;
 
-    app_state_guard.save_settings(&app_handle).map_err(|e| {
-        error!("Failed to save settings: {:?}", e);
+    {
+        let mut app_state_guard = state.lock().await;
+        let keep_versions = app_state_guard.settings.keep_versions;
+
+        if let Some(ref mut installed_metadata) = app_state_guard.installed_metadata {
+            let localization = snapshot.localization.clone();
+            let evicted = installed_metadata.record_install(
+                &localization,
+                &source,
+                snapshot,
+                components,
+                steam::read_game_build(&game_path),
+                keep_versions,
+            );
+            utils::remove_localization_snapshots(&game_path, &localization_id, &evicted);
+
+            if let Some(installed) = installed_metadata.installed.get_mut(&localization_id) {
+                installed.pinned = true;
+            }
+        }
+
+        app_state_guard.save_installed_metadata().map_err(|e| {
+            error!("Failed to save installed metadata: {:?}", e);
+            e.to_string()
+        })?;
+
+        app_handle
+            .emit("app_state_updated", app_state_guard.clone())
+            .map_err(|e| {
+                error!("Failed to emit app state updated: {:?}", e);
+                e.to_string()
+            })?;
+    }
+
+    rebuild_dependents(&app_handle, &state, &game_path, &localization_id).await;
+
+    Ok(())
+}
+
+#[tauri::command]
+async fn set_localization_pinned(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppStateMutex>,
+    localization_id: String,
+    pinned: bool,
+) -> Result<(), String> {
+    debug!(
+        "Setting localization {:?} pinned: {}",
+        localization_id, pinned
+    );
+
+    let mut app_state_guard = state.lock().await;
+
+    let installed = app_state_guard
+        .installed_metadata
+        .as_mut()
+        .and_then(|metadata| metadata.installed.get_mut(&localization_id))
+        .ok_or_else(|| "Localization is not installed".to_string())?;
+    installed.pinned = pinned;
+
+    app_state_guard.save_installed_metadata().map_err(|e| {
+        error!("Failed to save installed metadata: {:?}", e);
         e.to_string()
     })?;
 
@@ -386,29 +1053,1590 @@ async fn set_game_directory(
 }
 
 #[tauri::command]
-async fn update_and_play(
+async fn create_composite_localization(
     app_handle: tauri::AppHandle,
     state: State<'_, AppStateMutex>,
     localization_lock: State<'_, LocalizationLocks>,
-    remote_localizations_state: State<'_, RemoteLocalizationsMutex>,
+    composite: composite::CompositeLocalization,
 ) -> Result<(), String> {
-    debug!("Running update and play");
+    debug!("Creating composite localization: {:?}", composite);
 
-    app_handle
-        .emit("play:started", ())
-        .map_err(|e| e.to_string())?;
+    if game_running(&app_handle) {
+        return Err("Game is running".to_string());
+    }
+
+    if composite.id.is_empty()
+        || composite.id.starts_with('.')
+        || composite.id.contains(['/', '\\'])
+        || composite.depends_on(&composite.id)
+    {
+        return Err("Invalid composite localization id".to_string());
+    }
+
+    let game_path;
+    let validation;
+
+    {
+        let app_state_guard = state.lock().await;
+        validation = app_state_guard.settings.validation.clone();
+
+        game_path = app_state_guard.game_path().map_err(|e| {
+            error!("Failed to get game directory: {:?}", e);
+            e.to_string()
+        })?;
+
+        if let Some(metadata) = &app_state_guard.installed_metadata {
+            if metadata.installed.contains_key(&composite.id)
+                || metadata.stashed.contains_key(&composite.id)
+            {
+                return Err("A localization with this id is already installed".to_string());
+            }
+
+            if composite
+                .inputs()
+                .any(|input| metadata.composite_reads_from(input, &composite.id))
+            {
+                return Err("Composite localization cannot depend on itself".to_string());
+            }
+        }
+    }
+
+    let _acquired_lock = lock_localization(&localization_lock, &game_path, &composite.id).await;
+
+    let issues = utils::install_composite(&game_path, &composite, &validation).map_err(|e| {
+        error!("Failed to build composite localization: {:?}", e);
+        e.to_string()
+    })?;
+    emit_validation_issues(&app_handle, &composite.id, &issues);
+
+    {
+        let mut app_state_guard = state.lock().await;
+
+        app_state_guard
+            .installed_metadata
+            .get_or_insert_with(utils::InstalledMetadata::new)
+            .composites
+            .insert(composite.id.clone(), composite);
+
+        app_state_guard.save_installed_metadata().map_err(|e| {
+            error!("Failed to save installed metadata: {:?}", e);
+            e.to_string()
+        })?;
+
+        app_handle
+            .emit("app_state_updated", app_state_guard.clone())
+            .map_err(|e| {
+                error!("Failed to emit app state updated: {:?}", e);
+                e.to_string()
+            })?;
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+async fn remove_composite_localization(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppStateMutex>,
+    localization_lock: State<'_, LocalizationLocks>,
+    composite_id: String,
+) -> Result<(), String> {
+    debug!("Removing composite localization: {:?}", composite_id);
+
+    if game_running(&app_handle) {
+        return Err("Game is running".to_string());
+    }
+
+    let game_path = state.lock().await.game_path().map_err(|e| {
+        error!("Failed to get game directory: {:?}", e);
+        e.to_string()
+    })?;
+
+    let _acquired_lock = lock_localization(&localization_lock, &game_path, &composite_id).await;
+
+    let installed_metadata = state.lock().await.current_metadata();
+    utils::remove_composite(&game_path, &installed_metadata, &composite_id).map_err(|e| {
+        error!("Failed to remove composite localization: {:?}", e);
+        e.to_string()
+    })?;
+
+    {
+        let mut app_state_guard = state.lock().await;
+
+        if let Some(ref mut installed_metadata) = app_state_guard.installed_metadata {
+            installed_metadata.composites.remove(&composite_id);
+        }
+
+        app_state_guard.save_installed_metadata().map_err(|e| {
+            error!("Failed to save installed metadata: {:?}", e);
+            e.to_string()
+        })?;
+
+        app_handle
+            .emit("app_state_updated", app_state_guard.clone())
+            .map_err(|e| {
+                error!("Failed to emit app state updated: {:?}", e);
+                e.to_string()
+            })?;
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+async fn start_dev_mode(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppStateMutex>,
+    dev_watchers: State<'_, DevWatchers>,
+    localization_id: String,
+    path: String,
+) -> Result<(), String> {
+    debug!(
+        "Starting dev mode for {:?} from {:?}",
+        localization_id, path
+    );
+
+    if localization_id.is_empty()
+        || localization_id.starts_with('.')
+        || localization_id.contains(['/', '\\'])
+    {
+        return Err("Invalid localization id".to_string());
+    }
+
+    let source_path = std::path::PathBuf::from(&path);
+    if !source_path.is_dir() {
+        return Err("Folder does not exist".to_string());
+    }
+
+    let game_path;
+
+    {
+        let app_state_guard = state.lock().await;
+
+        game_path = app_state_guard.game_path().map_err(|e| {
+            error!("Failed to get game directory: {:?}", e);
+            e.to_string()
+        })?;
+
+        if let Some(metadata) = &app_state_guard.installed_metadata {
+            // Mirroring wipes Lang/<id>, only a previous dev folder may be replaced
+            if metadata
+                .installed
+                .get(&localization_id)
+                .is_some_and(|installed| installed.dev_folder.is_none())
+                || metadata.composites.contains_key(&localization_id)
+                || metadata.stashed.contains_key(&localization_id)
+            {
+                return Err("A localization with this id is already installed".to_string());
+            }
+        }
+    }
+
+    let watcher = start_dev_watcher(&app_handle, &game_path, &localization_id, &source_path)
+        .await
+        .map_err(|e| {
+            error!("Failed to start dev mode: {:?}", e);
+            e.to_string()
+        })?;
+
+    {
+        let mut app_state_guard = state.lock().await;
+
+        app_state_guard
+            .installed_metadata
+            .get_or_insert_with(utils::InstalledMetadata::new)
+            .record_dev_folder(&localization_id, &source_path);
+
+        app_state_guard.save_installed_metadata().map_err(|e| {
+            error!("Failed to save installed metadata: {:?}", e);
+            e.to_string()
+        })?;
+
+        app_handle
+            .emit("app_state_updated", app_state_guard.clone())
+            .map_err(|e| {
+                error!("Failed to emit app state updated: {:?}", e);
+                e.to_string()
+            })?;
+    }
+
+    dev_watchers.insert(localization_id, watcher);
+
+    Ok(())
+}
+
+#[tauri::command]
+async fn stop_dev_mode(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppStateMutex>,
+    dev_watchers: State<'_, DevWatchers>,
+    localization_id: String,
+) -> Result<(), String> {
+    debug!("Stopping dev mode for {:?}", localization_id);
+
+    dev_watchers
+        .remove(&localization_id)
+        .ok_or_else(|| "Dev mode is not running".to_string())?;
+
+    let mut app_state_guard = state.lock().await;
+
+    if let Some(installed) = app_state_guard
+        .installed_metadata
+        .as_mut()
+        .and_then(|metadata| metadata.installed.get_mut(&localization_id))
+    {
+        installed.dev_folder = None;
+    }
+
+    app_state_guard.save_installed_metadata().map_err(|e| {
+        error!("Failed to save installed metadata: {:?}", e);
+        e.to_string()
+    })?;
+
+    app_handle
+        .emit("app_state_updated", app_state_guard.clone())
+        .map_err(|e| {
+            error!("Failed to emit app state updated: {:?}", e);
+            e.to_string()
+        })?;
+
+    Ok(())
+}
+
+#[tauri::command]
+async fn export_localization(
+    state: State<'_, AppStateMutex>,
+    localization_lock: State<'_, LocalizationLocks>,
+    localization_id: String,
+    destination: String,
+    include_fonts: bool,
+) -> Result<utils::Localization, String> {
+    debug!(
+        "Exporting localization {:?} to {:?}, fonts: {}",
+        localization_id, destination, include_fonts
+    );
+
+    let game_path;
+    let installed;
+
+    {
+        let app_state_guard = state.lock().await;
+
+        game_path = app_state_guard.game_path().map_err(|e| {
+            error!("Failed to get game directory: {:?}", e);
+            e.to_string()
+        })?;
+
+        installed = app_state_guard
+            .installed_metadata
+            .as_ref()
+            .and_then(|metadata| metadata.installed.get(&localization_id))
+            .cloned();
+    }
+
+    let _acquired_lock = lock_localization(&localization_lock, &game_path, &localization_id).await;
+
+    utils::export_localization(
+        &game_path,
+        &localization_id,
+        installed.as_ref(),
+        std::path::Path::new(&destination),
+        include_fonts,
+    )
+    .map_err(|e| {
+        error!("Failed to export localization: {:?}", e);
+        e.to_string()
+    })
+}
+
+#[tauri::command]
+async fn set_active_localization(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppStateMutex>,
+    localization_id: String,
+) -> Result<(), String> {
+    debug!("Setting active localization: {:?}", localization_id);
+
+    if game_running(&app_handle) {
+        return Err("Game is running".to_string());
+    }
+
+    let mut app_state_guard = state.lock().await;
+
+    let game_path = app_state_guard.game_path().map_err(|e| {
+        error!("Failed to get game directory: {:?}", e);
+        e.to_string()
+    })?;
+
+    let installed_metadata = app_state_guard.current_metadata();
+    utils::set_active_localization(&game_path, &installed_metadata, &localization_id).map_err(
+        |e| {
+            error!("Failed to set active localization: {:?}", e);
+            e.to_string()
+        },
+    )?;
+    app_state_guard.active_localization = Some(localization_id);
+
+    app_handle
+        .emit("app_state_updated", app_state_guard.clone())
+        .map_err(|e| {
+            error!("Failed to emit app state updated: {:?}", e);
+            e.to_string()
+        })?;
+
+    Ok(())
+}
+
+/// Finds a localization in the active source, fetching it on first use.
+async fn find_profile_localization(
+    localization_id: &str,
+    source: Option<&(String, String)>,
+    remote_localizations: &mut Option<Vec<utils::Localization>>,
+) -> anyhow::Result<utils::Localization> {
+    let (_, source_url) = source.ok_or_else(|| anyhow::anyhow!("No active source selected"))?;
+
+    let remote = match remote_localizations {
+        Some(remote) => remote,
+        None => {
+            remote_localizations.insert(utils::fetch_available_localizations(source_url).await?)
+        }
+    };
+
+    remote
+        .iter()
+        .find_map(|localization| localization.resolve(localization_id))
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Localization '{}' not found in remote source",
+                localization_id
+            )
+        })
+}
+
+/// Brings one localization of a profile to the wanted version, from the
+/// archive cache if possible. Returns whether anything was installed.
+#[allow(clippy::too_many_arguments)]
+async fn apply_profile_localization(
+    app_handle: &tauri::AppHandle,
+    state: &AppStateMutex,
+    game_path: &std::path::PathBuf,
+    entry: &settings::ProfileLocalization,
+    source: Option<&(String, String)>,
+    remote_localizations: &mut Option<Vec<utils::Localization>>,
+    extraction_limits: &archive::ExtractionLimits,
+    validation: &validation::ValidationSettings,
+) -> anyhow::Result<bool> {
+    // Without a version the profile follows the latest release, the newest
+    // cached version is used only while the source cannot be reached
+    let version = match &entry.version {
+        Some(version) => Some(version.clone()),
+        None => match find_profile_localization(&entry.id, source, remote_localizations).await {
+            Ok(localization) => Some(localization.version),
+            Err(e) => {
+                error!(
+                    "Failed to find the latest version of {}, using the cached one: {:?}",
+                    entry.id, e
+                );
+                None
+            }
+        },
+    };
+
+    let (installed, cached, installed_metadata) = {
+        let app_state_guard = state.lock().await;
+        let metadata = app_state_guard.installed_metadata.as_ref();
+
+        (
+            metadata
+                .and_then(|metadata| metadata.installed.get(&entry.id))
+                .cloned(),
+            metadata
+                .and_then(|metadata| metadata.cached_snapshot(&entry.id, version.as_deref()))
+                .map(|(installed, snapshot)| {
+                    (
+                        snapshot.clone(),
+                        installed.source.clone(),
+                        installed.components.clone(),
+                    )
+                }),
+            app_state_guard.current_metadata(),
+        )
+    };
+
+    let localization_path = game_path
+        .join("LimbusCompany_Data")
+        .join("Lang")
+        .join(&entry.id);
+
+    if let Some(installed) = &installed {
+        let version_matches = version
+            .as_ref()
+            .map_or(true, |version| *version == installed.version);
+
+        if localization_path.exists() && version_matches {
+            if let Some(installed) = state
+                .lock()
+                .await
+                .installed_metadata
+                .as_mut()
+                .and_then(|metadata| metadata.installed.get_mut(&entry.id))
+            {
+                installed.pinned = entry.version.is_some();
+            }
+            return Ok(false);
+        }
+    }
+
+    let _ = app_handle.emit("profile:installing", &entry.id);
+
+    let (localization, snapshot, source_name, components) = match cached {
+        Some((snapshot, source_name, components)) => {
+            info!(
+                "Restoring localization {} version {} from cache",
+                entry.id, snapshot.version
+            );
+            let issues = utils::restore_localization_snapshot(
+                game_path,
+                &installed_metadata,
+                &snapshot,
+                components.as_deref(),
+                extraction_limits,
+                validation,
+            )?;
+            emit_validation_issues(app_handle, &entry.id, &issues);
+            utils::install_fonts_for_localization(game_path, &snapshot.localization).await?;
+
+            (
+                snapshot.localization.clone(),
+                snapshot,
+                source_name,
+                components,
+            )
+        }
+        None => {
+            let localization =
+                find_profile_localization(&entry.id, source, remote_localizations).await?;
+            let (source_name, _) =
+                source.ok_or_else(|| anyhow::anyhow!("No active source selected"))?;
+
+            if let Some(version) = &version {
+                if *version != localization.version {
+                    return Err(anyhow::anyhow!(
+                        "Version '{}' of '{}' is neither cached nor available",
+                        version,
+                        entry.id
+                    ));
+                }
+            }
+
+            let components = installed.and_then(|installed| installed.components);
+            let report = utils::install_localization(
+                game_path,
+                &installed_metadata,
+                &localization,
+                components.as_deref(),
+                extraction_limits,
+                validation,
+            )
+            .await?;
+            emit_validation_issues(app_handle, &entry.id, &report.issues);
+            utils::install_fonts_for_localization(game_path, &localization).await?;
+
+            (
+                localization,
+                report.snapshot,
+                source_name.clone(),
+                components,
+            )
+        }
+    };
+
+    let mut app_state_guard = state.lock().await;
+    let keep_versions = app_state_guard.settings.keep_versions;
+    let metadata = app_state_guard
+        .installed_metadata
+        .get_or_insert_with(utils::InstalledMetadata::new);
+
+    let evicted = metadata.record_install(
+        &localization,
+        &source_name,
+        snapshot,
+        components,
+        steam::read_game_build(game_path),
+        keep_versions,
+    );
+    utils::remove_localization_snapshots(game_path, &entry.id, &evicted);
+
+    // A profile asking for a specific version should not be updated away from it
+    if let Some(installed) = metadata.installed.get_mut(&entry.id) {
+        installed.pinned = entry.version.is_some();
+    }
+
+    Ok(true)
+}
+
+#[tauri::command]
+async fn apply_profile(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppStateMutex>,
+    localization_lock: State<'_, LocalizationLocks>,
+    profile_name: String,
+) -> Result<Vec<UpdateResult>, String> {
+    debug!("Applying profile: {:?}", profile_name);
+
+    if game_running(&app_handle) {
+        return Err("Game is running".to_string());
+    }
+
+    let profile;
+    let source;
+    let game_path;
+    let extraction_limits;
+    let validation;
+
+    {
+        let app_state_guard = state.lock().await;
+        extraction_limits = app_state_guard.settings.extraction_limits.clone();
+        validation = app_state_guard.settings.validation.clone();
+
+        profile = app_state_guard
+            .settings
+            .profiles
+            .get(&profile_name)
+            .cloned()
+            .ok_or_else(|| "Profile not found".to_string())?;
+
+        source = app_state_guard
+            .settings
+            .selected_source
+            .as_ref()
+            .and_then(|name| {
+                app_state_guard
+                    .settings
+                    .sources
+                    .get(name)
+                    .map(|source| (name.clone(), source.url.clone()))
+            });
+
+        game_path = app_state_guard.game_path().map_err(|e| {
+            error!("Failed to get game directory: {:?}", e);
+            e.to_string()
+        })?;
+    }
+
+    let mut summary = Vec::new();
+    let mut changed_ids = Vec::new();
+    let mut remote_localizations = None;
+
+    for entry in &profile.localizations {
+        let _acquired_lock = lock_localization(&localization_lock, &game_path, &entry.id).await;
+
+        match apply_profile_localization(
+            &app_handle,
+            &state,
+            &game_path,
+            entry,
+            source.as_ref(),
+            &mut remote_localizations,
+            &extraction_limits,
+            &validation,
+        )
+        .await
+        {
+            Ok(true) => {
+                changed_ids.push(entry.id.clone());
+                summary.push(UpdateResult::new(&entry.id, UpdateStatus::Updated));
+            }
+            Ok(false) => summary.push(UpdateResult::new(&entry.id, UpdateStatus::UpToDate)),
+            Err(e) => {
+                error!("Failed to apply profile localization {}: {:?}", entry.id, e);
+                summary.push(UpdateResult::failed(&entry.id, &e));
+            }
+        }
+    }
+
+    {
+        let mut app_state_guard = state.lock().await;
+        let metadata = app_state_guard
+            .installed_metadata
+            .get_or_insert_with(utils::InstalledMetadata::new);
+
+        let to_remove: Vec<String> = metadata
+            .installed
+            .values()
+            .filter(|installed| installed.dev_folder.is_none())
+            .filter(|installed| {
+                !profile
+                    .localizations
+                    .iter()
+                    .any(|entry| entry.id == installed.id)
+            })
+            .map(|installed| installed.id.clone())
+            .collect();
+
+        for localization_id in to_remove {
+            let _ = app_handle.emit("profile:removing", &localization_id);
+
+            match utils::stash_localization(&game_path, metadata, &localization_id) {
+                Ok(()) => summary.push(UpdateResult::new(&localization_id, UpdateStatus::Removed)),
+                Err(e) => {
+                    error!("Failed to remove localization {}: {:?}", localization_id, e);
+                    summary.push(UpdateResult::failed(&localization_id, &e));
+                }
+            }
+        }
+
+        if let Some(active) = &profile.active {
+            let installed_metadata = app_state_guard.current_metadata();
+            match utils::set_active_localization(&game_path, &installed_metadata, active) {
+                Ok(()) => app_state_guard.active_localization = Some(active.clone()),
+                Err(e) => {
+                    error!("Failed to activate localization {}: {:?}", active, e);
+                    summary.push(UpdateResult::failed(active, &e));
+                }
+            }
+        }
+
+        app_state_guard.save_installed_metadata().map_err(|e| {
+            error!("Failed to save installed metadata: {:?}", e);
+            e.to_string()
+        })?;
+
+        app_handle
+            .emit("app_state_updated", app_state_guard.clone())
+            .map_err(|e| {
+                error!("Failed to emit app state updated: {:?}", e);
+                e.to_string()
+            })?;
+    }
+
+    for localization_id in &changed_ids {
+        rebuild_dependents(&app_handle, &state, &game_path, localization_id).await;
+    }
+
+    let _ = app_handle.emit("profile:applied", &summary);
+    Ok(summary)
+}
+
+#[tauri::command]
+async fn save_profile(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppStateMutex>,
+    profile_name: String,
+) -> Result<(), String> {
+    debug!("Saving current setup as profile: {:?}", profile_name);
+
+    let mut app_state_guard = state.lock().await;
+    app_state_guard.load_active_localization();
+
+    let mut localizations: Vec<settings::ProfileLocalization> = app_state_guard
+        .installed_metadata
+        .iter()
+        .flat_map(|metadata| metadata.installed.values())
+        .filter(|installed| installed.dev_folder.is_none())
+        .map(|installed| settings::ProfileLocalization {
+            id: installed.id.clone(),
+            version: installed.pinned.then(|| installed.version.clone()),
+        })
+        .collect();
+    localizations.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let profile = settings::Profile {
+        localizations,
+        active: app_state_guard.active_localization.clone(),
+    };
+    app_state_guard
+        .settings
+        .profiles
+        .insert(profile_name, profile);
+
+    app_state_guard.save_settings(&app_handle).map_err(|e| {
+        error!("Failed to save settings: {:?}", e);
+        e.to_string()
+    })?;
+
+    app_handle
+        .emit("app_state_updated", app_state_guard.clone())
+        .map_err(|e| {
+            error!("Failed to emit app state updated: {:?}", e);
+            e.to_string()
+        })?;
+
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_localization_coverage(
+    state: State<'_, AppStateMutex>,
+    localization_id: String,
+) -> Result<coverage::CoverageReport, String> {
+    debug!("Computing coverage of localization: {:?}", localization_id);
+
+    let game_path = state.lock().await.game_path().map_err(|e| {
+        error!("Failed to get game directory: {:?}", e);
+        e.to_string()
+    })?;
+
+    coverage::localization_coverage(&game_path, &localization_id).map_err(|e| {
+        error!("Failed to compute localization coverage: {:?}", e);
+        e.to_string()
+    })
+}
+
+#[tauri::command]
+async fn set_game_directory(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppStateMutex>,
+    directory: Option<String>,
+) -> Result<(), String> {
+    debug!("Setting game directory to: {:?}", directory);
+
+    let mut app_state_guard = state.lock().await;
+
+    app_state_guard
+        .update_game_directory(&directory)
+        .map_err(|e| {
+            error!("Failed to update game directory: {:?}", e);
+            e.to_string()
+        })?;
+
+    app_state_guard.save_settings(&app_handle).map_err(|e| {
+        error!("Failed to save settings: {:?}", e);
+        e.to_string()
+    })?;
+
+    app_handle
+        .emit("app_state_updated", app_state_guard.clone())
+        .map_err(|e| {
+            error!("Failed to emit app state updated: {:?}", e);
+            e.to_string()
+        })?;
+
+    Ok(())
+}
+
+/// Installs newer versions of localizations that share one archive, the
+/// sub-packs of a bundle or a single localization, records them and
+/// rebuilds everything built on top of them. Returns one result per target.
+#[allow(clippy::too_many_arguments)]
+async fn install_update(
+    app_handle: &tauri::AppHandle,
+    state: &AppStateMutex,
+    localization_lock: &LocalizationLocks,
+    game_path: &std::path::PathBuf,
+    source: &str,
+    targets: &[(utils::Localization, Option<Vec<String>>)],
+    extraction_limits: &archive::ExtractionLimits,
+    validation: &validation::ValidationSettings,
+) -> Vec<anyhow::Result<()>> {
+    let target_ids: Vec<String> = targets
+        .iter()
+        .map(|(localization, _)| localization.id.clone())
+        .collect();
+    let mut installed_ids = Vec::new();
+    let mut results = Vec::new();
+
+    {
+        let _acquired_locks = lock_localizations(localization_lock, game_path, &target_ids).await;
+
+        let installed_metadata = state.lock().await.current_metadata();
+        let reports = match utils::install_shared_archive(
+            game_path,
+            &installed_metadata,
+            targets,
+            extraction_limits,
+            validation,
+        )
+        .await
+        {
+            Ok(reports) => reports,
+            Err(e) => {
+                return targets
+                    .iter()
+                    .map(|_| Err(anyhow::anyhow!("{:#}", e)))
+                    .collect()
+            }
+        };
+
+        let mut state_guard = state.lock().await;
+        let keep_versions = state_guard.settings.keep_versions;
+
+        for (report, (localization, components)) in reports.into_iter().zip(targets) {
+            let report = match report {
+                Ok(report) => report,
+                Err(e) => {
+                    results.push(Err(e));
+                    continue;
+                }
+            };
+            emit_validation_issues(app_handle, &localization.id, &report.issues);
+
+            if let Some(ref mut metadata) = state_guard.installed_metadata {
+                let evicted = metadata.record_install(
+                    localization,
+                    source,
+                    report.snapshot,
+                    components.clone(),
+                    steam::read_game_build(game_path),
+                    keep_versions,
+                );
+                utils::remove_localization_snapshots(game_path, &localization.id, &evicted);
+            }
+
+            installed_ids.push(localization.id.clone());
+            results.push(Ok(()));
+        }
+
+        if let Err(e) = state_guard.save_installed_metadata() {
+            error!("Failed to save installed metadata: {:?}", e);
+        }
+    }
+
+    for id in &installed_ids {
+        rebuild_dependents(app_handle, state, game_path, id).await;
+    }
+    results
+}
+
+/// Groups items with equal keys, keeping the order they first appear in.
+/// Updates are grouped by archive so a bundle is downloaded once.
+fn group_by_key<T, K: PartialEq>(items: Vec<T>, key: impl Fn(&T) -> K) -> Vec<Vec<T>> {
+    let mut groups: Vec<(K, Vec<T>)> = Vec::new();
+
+    for item in items {
+        let item_key = key(&item);
+        match groups
+            .iter_mut()
+            .find(|(group_key, _)| *group_key == item_key)
+        {
+            Some((_, group)) => group.push(item),
+            None => groups.push((item_key, vec![item])),
+        }
+    }
+
+    groups.into_iter().map(|(_, group)| group).collect()
+}
 
-    if steam::is_game_running() {
+/// Compares installed localizations with the source each one was installed
+/// from. Local, dev mode and pinned localizations are left out.
+async fn find_available_updates(
+    state: &AppStateMutex,
+    game_path: &std::path::Path,
+) -> anyhow::Result<Vec<AvailableUpdate>> {
+    let (installed, sources) = {
+        let app_state_guard = state.lock().await;
+        let installed: Vec<utils::InstalledLocalization> = app_state_guard
+            .installed_metadata
+            .iter()
+            .flat_map(|metadata| metadata.installed.values())
+            .filter(|installed| {
+                installed.dev_folder.is_none()
+                    && installed.source != utils::LOCAL_SOURCE
+                    && !installed.pinned
+            })
+            .cloned()
+            .collect();
+
+        (installed, app_state_guard.settings.sources.clone())
+    };
+
+    let mut remote_by_source: HashMap<String, Vec<utils::Localization>> = HashMap::new();
+    let mut updates = Vec::new();
+
+    for installed in installed {
+        if !remote_by_source.contains_key(&installed.source) {
+            let Some(source) = sources.get(&installed.source) else {
+                debug!(
+                    "Source {} of localization {} is not configured",
+                    installed.source, installed.id
+                );
+                continue;
+            };
+
+            let remote = match utils::fetch_available_localizations(&source.url).await {
+                Ok(remote) => remote,
+                Err(e) => {
+                    error!("Failed to fetch source {}: {:?}", installed.source, e);
+                    Vec::new()
+                }
+            };
+            remote_by_source.insert(installed.source.clone(), remote);
+        }
+
+        let Some(remote) = remote_by_source[&installed.source]
+            .iter()
+            .find_map(|localization| localization.resolve(&installed.id))
+        else {
+            continue;
+        };
+
+        let localization_path = game_path
+            .join("LimbusCompany_Data")
+            .join("Lang")
+            .join(&installed.id);
+
+        if localization_path.exists() && remote.version == installed.version {
+            continue;
+        }
+
+        updates.push(AvailableUpdate {
+            id: installed.id.clone(),
+            source: installed.source.clone(),
+            installed_version: installed.version.clone(),
+            version: remote.version.clone(),
+            size: remote.size,
+            localization: remote,
+            components: installed.components.clone(),
+        });
+    }
+
+    Ok(updates)
+}
+
+/// Runs one background check when enabled, installing the updates if asked to.
+async fn check_for_updates(app_handle: &tauri::AppHandle) -> anyhow::Result<()> {
+    let state = app_handle.state::<AppStateMutex>();
+
+    let (game_path, auto_update) = {
+        let app_state_guard = state.lock().await;
+        if !app_state_guard.settings.auto_update.enabled {
+            return Ok(());
+        }
+
+        (
+            app_state_guard.game_path()?,
+            app_state_guard.settings.auto_update.clone(),
+        )
+    };
+
+    let updates = find_available_updates(&state, &game_path).await?;
+    if updates.is_empty() {
+        debug!("No updates available");
+        return Ok(());
+    }
+
+    info!("{} localization update(s) available", updates.len());
+    app_handle.emit("updates_available", &updates)?;
+
+    if !auto_update.install {
+        return Ok(());
+    }
+
+    if game_running(app_handle) {
+        info!("Game is running, postponing automatic updates until it exits");
+        queue_action(app_handle, PendingAction::Update).await;
+        return Ok(());
+    }
+
+    let summary = install_updates(app_handle, updates).await?;
+    app_handle.emit("updates_installed", &summary)?;
+    Ok(())
+}
+
+async fn install_updates(
+    app_handle: &tauri::AppHandle,
+    updates: Vec<AvailableUpdate>,
+) -> anyhow::Result<Vec<UpdateResult>> {
+    let state = app_handle.state::<AppStateMutex>();
+    let localization_lock = app_handle.state::<LocalizationLocks>();
+
+    let (game_path, extraction_limits, validation) = {
+        let app_state_guard = state.lock().await;
+        (
+            app_state_guard.game_path()?,
+            app_state_guard.settings.extraction_limits.clone(),
+            app_state_guard.settings.validation.clone(),
+        )
+    };
+
+    let groups = group_by_key(updates, |update| {
+        (
+            update.source.clone(),
+            update.localization.url.clone(),
+            update.localization.version.clone(),
+        )
+    });
+
+    let mut summary = Vec::new();
+    for group in groups {
+        for update in &group {
+            info!(
+                "Automatically updating localization {} to version {}",
+                update.id, update.version
+            );
+        }
+
+        let targets: Vec<(utils::Localization, Option<Vec<String>>)> = group
+            .iter()
+            .map(|update| (update.localization.clone(), update.components.clone()))
+            .collect();
+        let results = install_update(
+            app_handle,
+            &state,
+            &localization_lock,
+            &game_path,
+            &group[0].source,
+            &targets,
+            &extraction_limits,
+            &validation,
+        )
+        .await;
+
+        for (update, result) in group.iter().zip(results) {
+            match result {
+                Ok(()) => summary.push(UpdateResult::new(&update.id, UpdateStatus::Updated)),
+                Err(e) => {
+                    error!("Failed to update localization {}: {:?}", update.id, e);
+                    summary.push(UpdateResult::failed(&update.id, &e));
+                }
+            }
+        }
+    }
+
+    app_handle.emit("app_state_updated", state.lock().await.clone())?;
+    Ok(summary)
+}
+
+async fn queue_action(app_handle: &tauri::AppHandle, action: PendingAction) {
+    let pending_actions = app_handle.state::<PendingActionsMutex>();
+    let mut pending_actions_guard = pending_actions.lock().await;
+
+    // Updates look at everything installed, one in the queue is enough
+    if matches!(action, PendingAction::Update)
+        && pending_actions_guard
+            .iter()
+            .any(|pending| matches!(pending, PendingAction::Update))
+    {
+        return;
+    }
+
+    pending_actions_guard.push(action);
+    let _ = app_handle.emit("pending_actions_updated", pending_actions_guard.clone());
+}
+
+/// Installs every available update of the installed localizations.
+async fn run_update_action(app_handle: &tauri::AppHandle) -> Vec<UpdateResult> {
+    let state = app_handle.state::<AppStateMutex>();
+    let game_path = state.lock().await.game_path();
+    let updates = match game_path {
+        Ok(game_path) => find_available_updates(&state, &game_path).await,
+        Err(e) => Err(e),
+    };
+
+    match updates {
+        Ok(updates) => match install_updates(app_handle, updates).await {
+            Ok(results) => results,
+            Err(e) => {
+                error!("Failed to install updates: {:?}", e);
+                Vec::new()
+            }
+        },
+        Err(e) => {
+            error!("Failed to check for updates: {:?}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Runs everything queued while the game was running, in order. Actions left
+/// when the game starts again go back to the queue.
+async fn run_pending_actions(app_handle: &tauri::AppHandle) {
+    let pending_actions = {
+        let pending_actions = app_handle.state::<PendingActionsMutex>();
+        let mut pending_actions_guard = pending_actions.lock().await;
+        std::mem::take(&mut *pending_actions_guard)
+    };
+
+    if pending_actions.is_empty() {
+        return;
+    }
+
+    info!("Running {} pending action(s)", pending_actions.len());
+    let _ = app_handle.emit("pending_actions_updated", Vec::<PendingAction>::new());
+
+    let mut summary = Vec::new();
+    let mut pending_actions = pending_actions.into_iter();
+    while let Some(action) = pending_actions.next() {
+        if game_running(app_handle) {
+            info!("Game started again, postponing the remaining pending actions");
+
+            let pending_state = app_handle.state::<PendingActionsMutex>();
+            let mut pending_actions_guard = pending_state.lock().await;
+            let queued_meanwhile = std::mem::take(&mut *pending_actions_guard);
+            pending_actions_guard.push(action);
+            pending_actions_guard.extend(pending_actions);
+            drop(pending_actions_guard);
+
+            for action in queued_meanwhile {
+                queue_action(app_handle, action).await;
+            }
+            break;
+        }
+
+        match action {
+            PendingAction::Install {
+                localization,
+                packs,
+                components,
+            } => {
+                let result = install_localization(
+                    app_handle.clone(),
+                    app_handle.state(),
+                    app_handle.state(),
+                    (*localization).clone(),
+                    packs,
+                    components,
+                )
+                .await;
+
+                summary.push(match result {
+                    Ok(()) => UpdateResult::new(&localization.id, UpdateStatus::Updated),
+                    Err(e) => UpdateResult::failed(&localization.id, &anyhow::anyhow!(e)),
+                });
+            }
+            PendingAction::Update => summary.extend(run_update_action(app_handle).await),
+        }
+    }
+
+    let _ = app_handle.emit("pending_actions_finished", &summary);
+}
+
+/// Watches the process list and emits `game_started` and `game_stopped`,
+/// queued actions run as soon as the game is gone.
+fn spawn_game_monitor(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut monitor = steam::GameMonitor::new();
+        let mut was_running = false;
+
+        loop {
+            let game_path = app_handle
+                .state::<AppStateMutex>()
+                .lock()
+                .await
+                .game_path()
+                .ok();
+            let running = monitor.is_game_running(game_path.as_deref());
+            app_handle
+                .state::<GameRunning>()
+                .store(running, std::sync::atomic::Ordering::Relaxed);
+
+            if running != was_running {
+                was_running = running;
+
+                if running {
+                    info!("Game started");
+                    let _ = app_handle.emit("game_started", ());
+                } else {
+                    info!("Game stopped");
+                    let _ = app_handle.emit("game_stopped", ());
+
+                    // Runs on its own, so the monitor notices if the game starts again
+                    let pending_handle = app_handle.clone();
+                    tauri::async_runtime::spawn(async move {
+                        // Steam updates the game while it is closed
+                        if let Err(e) = check_game_build(&pending_handle).await {
+                            debug!("Skipping game build check: {:?}", e);
+                        }
+                        run_pending_actions(&pending_handle).await;
+                    });
+                }
+            }
+
+            tokio::time::sleep(GAME_MONITOR_INTERVAL).await;
+        }
+    });
+}
+
+/// How an installed localization fared after the game was updated.
+#[derive(Clone, Serialize)]
+struct BuildCheckResult {
+    id: String,
+    version: String,
+    repaired: bool,
+    error: Option<String>,
+    supported: Option<bool>, // Whether the installed version lists the new build
+    supported_update: Option<String>, // Newer version listing the new build
+}
+
+#[derive(Clone, Serialize)]
+struct GameUpdatedReport {
+    previous_build: String,
+    build: String,
+    localizations: Vec<BuildCheckResult>,
+}
+
+/// Verifies every installed localization against a new game build and
+/// restores the broken ones from their cached archives. Returns a result per
+/// localization and the ids that were restored. The CLI runs this without
+/// locks, the GUI passes its own.
+async fn verify_after_game_update(
+    game_path: &std::path::PathBuf,
+    installed_metadata: &utils::InstalledMetadata,
+    sources: &HashMap<String, settings::LocalizationSource>,
+    build: &str,
+    extraction_limits: &archive::ExtractionLimits,
+    validation: &validation::ValidationSettings,
+    localization_lock: Option<&LocalizationLocks>,
+) -> (Vec<BuildCheckResult>, Vec<String>) {
+    let mut installed: Vec<&utils::InstalledLocalization> = installed_metadata
+        .installed
+        .values()
+        .filter(|installed| installed.dev_folder.is_none())
+        .collect();
+    installed.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let mut remote_by_source: HashMap<String, Vec<utils::Localization>> = HashMap::new();
+    let mut results = Vec::new();
+    let mut repaired_ids = Vec::new();
+
+    for installed in installed {
+        if !remote_by_source.contains_key(&installed.source) {
+            let remote = match sources.get(&installed.source) {
+                Some(source) => utils::fetch_available_localizations(&source.url)
+                    .await
+                    .unwrap_or_else(|e| {
+                        error!("Failed to fetch source {}: {:?}", installed.source, e);
+                        Vec::new()
+                    }),
+                None => Vec::new(),
+            };
+            remote_by_source.insert(installed.source.clone(), remote);
+        }
+
+        let mut result = BuildCheckResult {
+            id: installed.id.clone(),
+            version: installed.version.clone(),
+            repaired: false,
+            error: None,
+            supported: None,
+            supported_update: None,
+        };
+
+        let remote: Vec<utils::Localization> = remote_by_source[&installed.source]
+            .iter()
+            .filter_map(|localization| localization.resolve(&installed.id))
+            .collect();
+        let installed_localization = remote
+            .iter()
+            .find(|remote| remote.version == installed.version)
+            .or_else(|| installed.snapshots.first().map(|s| &s.localization));
+
+        if let Some(localization) = installed_localization {
+            if !localization.game_builds.is_empty() {
+                result.supported = Some(localization.game_builds.iter().any(|b| b == build));
+            }
+        }
+        result.supported_update = remote
+            .iter()
+            .find(|remote| {
+                remote.version != installed.version && remote.game_builds.iter().any(|b| b == build)
+            })
+            .map(|remote| remote.version.clone());
+
+        let _acquired_lock = match localization_lock {
+            Some(localization_lock) => {
+                Some(lock_localization(localization_lock, game_path, &installed.id).await)
+            }
+            None => None,
+        };
+
+        let localization_path = game_path
+            .join("LimbusCompany_Data")
+            .join("Lang")
+            .join(&installed.id);
+
+        let broken = !localization_path.is_dir()
+            || validation::validate_directory(&localization_path, validation)
+                .map(|issues| validation::has_errors(&issues))
+                .unwrap_or(true);
+
+        if broken {
+            info!("Repairing localization {} after game update", installed.id);
+
+            let repair = installed
+                .snapshots
+                .first()
+                .ok_or_else(|| anyhow::anyhow!("No cached archive to restore from"))
+                .and_then(|snapshot| {
+                    utils::restore_localization_snapshot(
+                        game_path,
+                        installed_metadata,
+                        snapshot,
+                        installed.components.as_deref(),
+                        extraction_limits,
+                        validation,
+                    )
+                });
+
+            match repair {
+                Ok(_) => {
+                    result.repaired = true;
+                    repaired_ids.push(installed.id.clone());
+                }
+                Err(e) => {
+                    error!("Failed to repair localization {}: {:?}", installed.id, e);
+                    result.error = Some(format!("{:#}", e));
+                }
+            }
+        }
+
+        results.push(result);
+    }
+
+    // The game ships new English files, composites falling back to them are stale
+    repaired_ids.push(composite::ENGLISH_FALLBACK.to_string());
+
+    if let Err(e) = utils::validate_game_config(game_path) {
+        error!("Failed to validate game config: {:?}", e);
+    }
+
+    (results, repaired_ids)
+}
+
+/// Compares the game build with the one seen last. After a game update every
+/// installed localization is verified and restored from its cached archive
+/// when its files are missing or broken.
+async fn check_game_build(app_handle: &tauri::AppHandle) -> anyhow::Result<()> {
+    let state = app_handle.state::<AppStateMutex>();
+    let localization_lock = app_handle.state::<LocalizationLocks>();
+
+    let (game_path, installed_metadata, sources, extraction_limits, validation) = {
+        let app_state_guard = state.lock().await;
+        let game_path = app_state_guard.game_path()?;
+        let Some(installed_metadata) = app_state_guard.installed_metadata.clone() else {
+            return Ok(());
+        };
+
+        (
+            game_path,
+            installed_metadata,
+            app_state_guard.settings.sources.clone(),
+            app_state_guard.settings.extraction_limits.clone(),
+            app_state_guard.settings.validation.clone(),
+        )
+    };
+
+    let Some(build) = steam::read_game_manifest(&game_path)?.buildid else {
+        return Ok(());
+    };
+
+    if installed_metadata.game_build.as_ref() == Some(&build) {
+        return Ok(());
+    }
+
+    if game_running(app_handle) {
+        debug!("Game is running, postponing the build check");
+        return Ok(());
+    }
+
+    let previous_build = installed_metadata.game_build.clone();
+    let mut results = Vec::new();
+
+    if let Some(previous_build) = &previous_build {
+        info!("Game updated from build {} to {}", previous_build, build);
+
+        let repaired_ids;
+        (results, repaired_ids) = verify_after_game_update(
+            &game_path,
+            &installed_metadata,
+            &sources,
+            &build,
+            &extraction_limits,
+            &validation,
+            Some(&localization_lock),
+        )
+        .await;
+
+        for id in &repaired_ids {
+            rebuild_dependents(app_handle, &state, &game_path, id).await;
+        }
+    } else {
+        debug!("Recording game build {}", build);
+    }
+
+    {
+        let mut app_state_guard = state.lock().await;
+        if let Some(metadata) = app_state_guard.installed_metadata.as_mut() {
+            metadata.game_build = Some(build.clone());
+        }
+        app_state_guard.save_installed_metadata()?;
+        let _ = app_handle.emit("app_state_updated", app_state_guard.clone());
+    }
+
+    if let Some(previous_build) = previous_build {
+        let _ = app_handle.emit(
+            "game_updated",
+            GameUpdatedReport {
+                previous_build,
+                build,
+                localizations: results,
+            },
+        );
+    }
+
+    Ok(())
+}
+
+/// Polls the sources for as long as the app runs. Settings are read on
+/// every round, so changes apply from the next check on.
+fn spawn_update_checker(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let interval_minutes = app_handle
+                .state::<AppStateMutex>()
+                .lock()
+                .await
+                .settings
+                .auto_update
+                .interval_minutes;
+
+            let interval = std::time::Duration::from_secs(interval_minutes.max(1) * 60);
+            tokio::time::sleep(interval).await;
+
+            if let Err(e) = check_for_updates(&app_handle).await {
+                error!("Failed to check for updates: {:?}", e);
+            }
+        }
+    });
+}
+
+#[tauri::command]
+async fn get_game_running(app_handle: tauri::AppHandle) -> Result<bool, String> {
+    Ok(game_running(&app_handle))
+}
+
+#[tauri::command]
+async fn get_pending_actions(
+    pending_actions: State<'_, PendingActionsMutex>,
+) -> Result<Vec<PendingAction>, String> {
+    Ok(pending_actions.lock().await.clone())
+}
+
+/// Installs right away, or once the game exits if it is running.
+#[tauri::command]
+async fn queue_install_localization(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppStateMutex>,
+    localization_lock: State<'_, LocalizationLocks>,
+    localization: utils::Localization,
+    packs: Option<Vec<String>>,
+    components: Option<Vec<String>>,
+) -> Result<bool, String> {
+    if !game_running(&app_handle) {
+        install_localization(
+            app_handle,
+            state,
+            localization_lock,
+            localization,
+            packs,
+            components,
+        )
+        .await?;
+        return Ok(false);
+    }
+
+    debug!("Game is running, queueing install of {}", localization.id);
+    queue_action(
+        &app_handle,
+        PendingAction::Install {
+            localization: Box::new(localization),
+            packs,
+            components,
+        },
+    )
+    .await;
+    Ok(true)
+}
+
+/// Updates everything right away, or once the game exits if it is running.
+#[tauri::command]
+async fn queue_update(app_handle: tauri::AppHandle) -> Result<bool, String> {
+    if !game_running(&app_handle) {
+        let summary = run_update_action(&app_handle).await;
+        let _ = app_handle.emit("updates_installed", &summary);
+        return Ok(false);
+    }
+
+    debug!("Game is running, queueing update");
+    queue_action(&app_handle, PendingAction::Update).await;
+    Ok(true)
+}
+
+#[tauri::command]
+async fn clear_pending_actions(
+    app_handle: tauri::AppHandle,
+    pending_actions: State<'_, PendingActionsMutex>,
+) -> Result<(), String> {
+    pending_actions.lock().await.clear();
+    let _ = app_handle.emit("pending_actions_updated", Vec::<PendingAction>::new());
+    Ok(())
+}
+
+/// Steam's manifest of the configured game, with its build and state.
+#[tauri::command]
+async fn get_game_manifest(state: State<'_, AppStateMutex>) -> Result<steam::AppManifest, String> {
+    let game_path = state.lock().await.game_path().map_err(|e| {
+        error!("Failed to get game directory: {:?}", e);
+        e.to_string()
+    })?;
+
+    steam::read_game_manifest(&game_path).map_err(|e| {
+        error!("Failed to read game manifest: {:?}", e);
+        e.to_string()
+    })
+}
+
+/// Shows what Play would run with the current launch settings, without
+/// running it.
+#[tauri::command]
+async fn test_launch(
+    state: State<'_, AppStateMutex>,
+) -> Result<Vec<launch::LaunchCommand>, String> {
+    let app_state_guard = state.lock().await;
+    let game_path = app_state_guard.game_path().ok();
+
+    launch::launch_commands(&app_state_guard.settings.launch, game_path.as_deref()).map_err(|e| {
+        error!("Failed to build launch command: {:?}", e);
+        e.to_string()
+    })
+}
+
+#[tauri::command]
+async fn update_and_play(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppStateMutex>,
+    localization_lock: State<'_, LocalizationLocks>,
+    remote_localizations_state: State<'_, RemoteLocalizationsMutex>,
+) -> Result<(), String> {
+    debug!("Running update and play");
+
+    app_handle
+        .emit("play:started", ())
+        .map_err(|e| e.to_string())?;
+
+    if game_running(&app_handle) {
         let _ = app_handle.emit("play:game_running", ());
         return Err("Game is already running".to_string());
     }
 
+    if let Err(e) = check_game_build(&app_handle).await {
+        debug!("Skipping game build check: {:?}", e);
+    }
+
     let active_source;
     let source_url;
     let game_path;
+    let strict_updates;
+    let extraction_limits;
+    let validation;
+    let launch_settings;
 
     {
         let app_state_guard = state.lock().await;
+        strict_updates = app_state_guard.settings.strict_updates;
+        launch_settings = app_state_guard.settings.launch.clone();
+        extraction_limits = app_state_guard.settings.extraction_limits.clone();
+        validation = app_state_guard.settings.validation.clone();
         active_source = app_state_guard
             .settings
             .selected_source
@@ -430,106 +2658,148 @@ async fn update_and_play(
         })?;
     }
 
-    let remote_localizations = utils::fetch_available_localizations(&source_url)
-        .await
-        .map_err(|e| {
-            error!("Failed to fetch available localizations: {:?}", e);
-            e.to_string()
-        })?;
-
-    {
-        let mut remote_localizations_guard = remote_localizations_state.lock().await;
-        let remote_localizations_payload = RemoteLocalizations {
-            source: active_source.clone(),
-            localizations: remote_localizations.clone(),
-        };
-
-        *remote_localizations_guard = Some(remote_localizations_payload.clone());
-        app_handle
-            .emit("remote_localizations_updated", remote_localizations_payload)
-            .map_err(|e| e.to_string())?;
-    }
-
-    let localizations_to_update: Vec<_> = state
-        .lock()
-        .await
-        .installed_metadata
-        .as_ref()
-        .ok_or_else(|| "No installed metadata found".to_string())?
-        .installed
-        .values()
-        .filter_map(|localization| {
-            let remote_localization = remote_localizations
-                .iter()
-                .find(|l| l.id == localization.id);
-
-            let Some(remote) = remote_localization else {
-                info!(
-                    "Localization {} not found in remote source",
-                    &localization.id
-                );
-                let _ = app_handle.emit("play:unknown_localization", &localization.id);
-                return None;
-            };
-
-            let localization_path = game_path
-                .join("LimbusCompany_Data")
-                .join("Lang")
-                .join(&localization.id);
-
-            if localization_path.exists() && remote.version == localization.version {
-                info!("Localization {} is up to date", &localization.id);
-                let _ = app_handle.emit("play:up_to_date", &localization.id);
-                return None;
+    let mut summary = Vec::new();
+
+    match utils::fetch_available_localizations(&source_url).await {
+        Ok(remote_localizations) => {
+            {
+                let mut remote_localizations_guard = remote_localizations_state.lock().await;
+                let remote_localizations_payload = RemoteLocalizations {
+                    source: active_source.clone(),
+                    localizations: remote_localizations.clone(),
+                };
+
+                *remote_localizations_guard = Some(remote_localizations_payload.clone());
+                app_handle
+                    .emit("remote_localizations_updated", remote_localizations_payload)
+                    .map_err(|e| e.to_string())?;
             }
 
-            Some((localization.id.clone(), remote.clone()))
-        })
-        .collect();
-
-    for (localization_id, remote_localization) in localizations_to_update {
-        info!(
-            "Updating localization {} to version {}",
-            &localization_id, &remote_localization.version
-        );
-        let _ = app_handle.emit("play:updating", &localization_id);
-
-        let lock = localization_lock
-            .entry((localization_id.clone(), game_path.clone()))
-            .or_insert_with(|| Mutex::new(()));
-        let _acquired_lock = lock.lock().await;
-
-        utils::install_localization(&game_path, &remote_localization)
-            .await
-            .map_err(|e| {
-                error!("Failed to install localization: {:?}", e);
-                e.to_string()
-            })?;
-
-        utils::install_fonts_for_localization(&game_path, &remote_localization)
-            .await
-            .map_err(|e| {
-                error!("Failed to install fonts for localization: {:?}", e);
-                e.to_string()
-            })?;
+            let mut localizations_to_update = Vec::new();
+
+            for localization in state
+                .lock()
+                .await
+                .installed_metadata
+                .as_ref()
+                .ok_or_else(|| "No installed metadata found".to_string())?
+                .installed
+                .values()
+            {
+                if localization.dev_folder.is_some() {
+                    info!("Localization {} is in dev mode, skipping", &localization.id);
+                    let _ = app_handle.emit("play:dev_mode", &localization.id);
+                    summary.push(UpdateResult::new(&localization.id, UpdateStatus::DevMode));
+                    continue;
+                }
+
+                if localization.source == utils::LOCAL_SOURCE {
+                    info!(
+                        "Localization {} is installed locally, skipping",
+                        &localization.id
+                    );
+                    let _ = app_handle.emit("play:local", &localization.id);
+                    summary.push(UpdateResult::new(&localization.id, UpdateStatus::Local));
+                    continue;
+                }
+
+                let remote_localization = remote_localizations
+                    .iter()
+                    .find_map(|l| l.resolve(&localization.id));
+
+                let Some(remote) = remote_localization else {
+                    info!(
+                        "Localization {} not found in remote source",
+                        &localization.id
+                    );
+                    let _ = app_handle.emit("play:unknown_localization", &localization.id);
+                    summary.push(UpdateResult::new(&localization.id, UpdateStatus::Unknown));
+                    continue;
+                };
+
+                if localization.pinned {
+                    info!("Localization {} is pinned, skipping", &localization.id);
+                    let _ = app_handle.emit("play:pinned", &localization.id);
+                    summary.push(UpdateResult::new(&localization.id, UpdateStatus::Pinned));
+                    continue;
+                }
+
+                let localization_path = game_path
+                    .join("LimbusCompany_Data")
+                    .join("Lang")
+                    .join(&localization.id);
+
+                if localization_path.exists() && remote.version == localization.version {
+                    info!("Localization {} is up to date", &localization.id);
+                    let _ = app_handle.emit("play:up_to_date", &localization.id);
+                    summary.push(UpdateResult::new(&localization.id, UpdateStatus::UpToDate));
+                    continue;
+                }
+
+                localizations_to_update.push((
+                    localization.id.clone(),
+                    remote,
+                    localization.components.clone(),
+                ));
+            }
 
-        let _ = app_handle.emit("play:update_finished", &localization_id);
+            let groups = group_by_key(localizations_to_update, |(_, remote, _)| {
+                (remote.url.clone(), remote.version.clone())
+            });
+
+            for group in groups {
+                for (localization_id, remote_localization, _) in &group {
+                    info!(
+                        "Updating localization {} to version {}",
+                        localization_id, &remote_localization.version
+                    );
+                    let _ = app_handle.emit("play:updating", localization_id);
+                }
+
+                let targets: Vec<(utils::Localization, Option<Vec<String>>)> = group
+                    .iter()
+                    .map(|(_, remote, components)| (remote.clone(), components.clone()))
+                    .collect();
+                let results = install_update(
+                    &app_handle,
+                    &state,
+                    &localization_lock,
+                    &game_path,
+                    &active_source,
+                    &targets,
+                    &extraction_limits,
+                    &validation,
+                )
+                .await;
+
+                for ((localization_id, _, _), result) in group.iter().zip(results) {
+                    match result {
+                        Ok(()) => {
+                            let _ = app_handle.emit("play:update_finished", localization_id);
+                            summary.push(UpdateResult::new(localization_id, UpdateStatus::Updated));
+                        }
+                        Err(e) => {
+                            error!("Failed to update localization {}: {:?}", localization_id, e);
+                            let _ = app_handle.emit("play:update_failed", localization_id);
+                            summary.push(UpdateResult::failed(localization_id, &e));
+                        }
+                    }
+                }
+            }
+        }
+        Err(e) => {
+            error!("Failed to fetch available localizations: {:?}", e);
 
-        {
-            let mut state_guard = state.lock().await;
-            if let Some(ref mut metadata) = state_guard.installed_metadata {
-                metadata.installed.insert(
-                    remote_localization.id.clone(),
-                    utils::InstalledLocalization {
-                        id: remote_localization.id.clone(),
-                        version: remote_localization.version.clone(),
-                        source: active_source.clone(),
-                    },
-                );
+            if strict_updates {
+                return Err(e.to_string());
             }
+
+            let _ = app_handle.emit("play:fetch_failed", e.to_string());
         }
     }
 
+    let _ = app_handle.emit("play:summary", &summary);
+
     let state_guard = state.lock().await;
     state_guard.save_installed_metadata().map_err(|e| {
         error!("Failed to save installed metadata: {:?}", e);
@@ -543,6 +2813,15 @@ async fn update_and_play(
             e.to_string()
         })?;
 
+    let failed = summary
+        .iter()
+        .filter(|result| matches!(result.status, UpdateStatus::Failed))
+        .count();
+
+    if strict_updates && failed > 0 {
+        return Err(format!("Failed to update {} localization(s)", failed));
+    }
+
     if let Err(e) = utils::validate_game_config(&game_path) {
         error!("Failed to validate game config: {:?}", e);
     }
@@ -550,7 +2829,7 @@ async fn update_and_play(
     app_handle
         .emit("play:starting_game", ())
         .map_err(|e| e.to_string())?;
-    steam::launch_game().map_err(|e| {
+    launch::launch_game(&launch_settings, Some(&game_path)).map_err(|e| {
         error!("Failed to launch game: {:?}", e);
         e.to_string()
     })?;
@@ -607,6 +2886,33 @@ pub fn run() {
 
             let localization_locks_mutex: LocalizationLocks = DashMap::new();
             app.manage(localization_locks_mutex);
+            app.manage(DevWatchers::new());
+            app.manage(PendingActionsMutex::new(Vec::new()));
+            app.manage(GameRunning::new(false));
+
+            match settings::acquire_gui_lock(app_handle) {
+                Ok(gui_lock) => {
+                    app.manage(gui_lock);
+                }
+                Err(e) => error!("Failed to write GUI lock file: {:?}", e),
+            }
+
+            spawn_update_checker(app_handle.clone());
+            spawn_game_monitor(app_handle.clone());
+
+            let dev_mode_handle = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = resume_dev_mode(&dev_mode_handle).await {
+                    error!("Failed to resume dev mode: {:?}", e);
+                }
+            });
+
+            let build_check_handle = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = check_game_build(&build_check_handle).await {
+                    debug!("Skipping game build check: {:?}", e);
+                }
+            });
 
             Ok(())
         })
@@ -617,9 +2923,28 @@ pub fn run() {
             get_latest_version,
             update_settings,
             install_localization,
+            install_local_localization,
             uninstall_localization,
             repair_localization,
+            rollback_localization,
+            set_localization_pinned,
+            get_localization_coverage,
+            set_active_localization,
+            apply_profile,
+            save_profile,
+            export_localization,
+            create_composite_localization,
+            remove_composite_localization,
+            start_dev_mode,
+            stop_dev_mode,
             set_game_directory,
+            test_launch,
+            get_game_running,
+            get_game_manifest,
+            get_pending_actions,
+            queue_install_localization,
+            queue_update,
+            clear_pending_actions,
             update_and_play,
         ])
         .run(tauri::generate_context!())
@@ -1,6 +1,41 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+use limbus_localization_manager_lib::cli::{self, Invocation};
+
 fn main() {
-    limbus_localization_manager_lib::run()
+    let program = std::env::args().next();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    match cli::parse_invocation(program.as_deref(), &args) {
+        Invocation::Wrap(command) => {
+            attach_parent_console();
+            std::process::exit(cli::wrap(command));
+        }
+        Invocation::Command(args) => {
+            attach_parent_console();
+            std::process::exit(cli::run(args));
+        }
+        Invocation::Gui => limbus_localization_manager_lib::run(),
+    }
 }
+
+/// Release builds have no console of their own, CLI output goes to the
+/// terminal the command was started from.
+#[cfg(windows)]
+fn attach_parent_console() {
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+
+    // Fails without a parent console, e.g. when Steam runs the wrapper
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(windows))]
+fn attach_parent_console() {}
@@ -0,0 +1,164 @@
+use anyhow::Context;
+use log::debug;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{cmp::Ordering, fs, path::Path};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct OverlayBase {
+    pub id: String,               // Id of the localization the overlay is applied on
+    pub versions: Option<String>, // Accepted base versions, e.g. ">=1.2, <2"
+}
+
+/// Compares versions segment by segment, numeric segments by value.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let split = |version: &str| -> Vec<String> {
+        version
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|segment| !segment.is_empty())
+            .map(str::to_string)
+            .collect()
+    };
+
+    let a = split(a);
+    let b = split(b);
+
+    for i in 0..a.len().max(b.len()) {
+        let left = a.get(i).map(String::as_str).unwrap_or("0");
+        let right = b.get(i).map(String::as_str).unwrap_or("0");
+
+        let ordering = match (left.parse::<u64>(), right.parse::<u64>()) {
+            (Ok(left), Ok(right)) => left.cmp(&right),
+            _ => left.cmp(right),
+        };
+
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    Ordering::Equal
+}
+
+/// Checks a version against comma separated constraints such as
+/// `>=1.2, <2`. A bare version must match exactly, `*` matches anything.
+pub fn version_matches(version: &str, range: &str) -> bool {
+    range
+        .split(',')
+        .map(str::trim)
+        .filter(|constraint| !constraint.is_empty())
+        .all(|constraint| {
+            if constraint == "*" {
+                return true;
+            }
+
+            let (operator, expected) = [">=", "<=", "==", ">", "<", "="]
+                .iter()
+                .find_map(|operator| {
+                    constraint
+                        .strip_prefix(operator)
+                        .map(|rest| (*operator, rest.trim()))
+                })
+                .unwrap_or(("=", constraint));
+
+            let ordering = compare_versions(version, expected);
+            match operator {
+                ">=" => ordering != Ordering::Less,
+                "<=" => ordering != Ordering::Greater,
+                ">" => ordering == Ordering::Greater,
+                "<" => ordering == Ordering::Less,
+                _ => ordering == Ordering::Equal,
+            }
+        })
+}
+
+/// Applies overlay files on top of an already populated localization
+/// directory. JSON files present in both are merged key by key, everything
+/// else replaces the base file.
+pub fn apply_overlay(overlay_dir: &Path, target_dir: &Path) -> Result<(), anyhow::Error> {
+    for entry in fs::read_dir(overlay_dir)
+        .with_context(|| format!("Failed to read overlay directory {:?}", overlay_dir))?
+    {
+        let entry = entry.with_context(|| format!("Error reading entry in overlay dir"))?;
+        let source_path = entry.path();
+        let destination_path = target_dir.join(entry.file_name());
+
+        if source_path.is_dir() {
+            fs::create_dir_all(&destination_path)
+                .with_context(|| format!("Failed to create directory {:?}", destination_path))?;
+            apply_overlay(&source_path, &destination_path)?;
+            continue;
+        }
+
+        let is_json = source_path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+
+        if is_json && destination_path.is_file() {
+            debug!("Merging {:?} into {:?}", source_path, destination_path);
+            merge_json_file(&source_path, &destination_path)?;
+        } else {
+            debug!("Copying {:?} -> {:?}", source_path, destination_path);
+            fs::copy(&source_path, &destination_path).with_context(|| {
+                format!(
+                    "Failed to copy file {:?} to {:?}",
+                    source_path, destination_path
+                )
+            })?;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn read_json(path: &Path) -> Result<Value, anyhow::Error> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read JSON file {:?}", path))?;
+
+    serde_json::from_str(content.trim_start_matches('\u{feff}'))
+        .with_context(|| format!("Failed to parse JSON file {:?}", path))
+}
+
+fn merge_json_file(overlay_path: &Path, base_path: &Path) -> Result<(), anyhow::Error> {
+    let mut base = read_json(base_path)?;
+    merge_json(&mut base, read_json(overlay_path)?);
+
+    let content = serde_json::to_string_pretty(&base)
+        .with_context(|| format!("Failed to serialize JSON file {:?}", base_path))?;
+    fs::write(base_path, content)
+        .with_context(|| format!("Failed to write JSON file {:?}", base_path))?;
+
+    Ok(())
+}
+
+/// Objects are merged recursively, arrays of objects with an `id` are
+/// merged by id and any other value is replaced.
+pub fn merge_json(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_json(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (Value::Array(base), Value::Array(overlay))
+            if overlay.iter().all(|item| item.get("id").is_some()) =>
+        {
+            for item in overlay {
+                let existing = base
+                    .iter_mut()
+                    .find(|existing| existing.get("id") == item.get("id"));
+
+                match existing {
+                    Some(existing) => merge_json(existing, item),
+                    None => base.push(item),
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
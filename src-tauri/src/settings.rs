@@ -1,12 +1,22 @@
+use crate::archive::ExtractionLimits;
+use crate::launch::LaunchSettings;
+use crate::validation::ValidationSettings;
 use log::{debug, error, info, warn};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
 use tauri::path::BaseDirectory;
 use tauri::Manager;
 
 const CURRENT_CONFIG_VERSION: u32 = 1;
+const DEFAULT_KEEP_VERSIONS: usize = 3;
+
+// Same as `identifier` in tauri.conf.json, the CLI has no AppHandle to ask
+const APP_IDENTIFIER: &str = "com.kimght.LimbusLocalizationManager";
+const BUNDLED_DEFAULTS: &str = include_str!("../resources/default_config.toml");
+const GUI_LOCK_FILE_NAME: &str = "gui.lock";
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct LocalizationSource {
@@ -14,6 +24,53 @@ pub struct LocalizationSource {
     pub url: String,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct AutoUpdateSettings {
+    pub enabled: bool,         // Poll sources in the background
+    pub interval_minutes: u64, // Time between checks
+    pub install: bool,         // Install found updates while the game is not running
+}
+
+impl Default for AutoUpdateSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_minutes: 60,
+            install: false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct WrapperSettings {
+    pub timeout_seconds: u64, // Updates still running after this are abandoned
+    pub progress: bool,       // Print what is being updated
+}
+
+impl Default for WrapperSettings {
+    fn default() -> Self {
+        Self {
+            timeout_seconds: 20,
+            progress: false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ProfileLocalization {
+    pub id: String,
+    pub version: Option<String>, // Whatever version is installed or latest if not set
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Profile {
+    #[serde(default)]
+    pub localizations: Vec<ProfileLocalization>,
+    pub active: Option<String>, // Language to activate, left as is if not set
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct AppSettings {
     #[serde(default)]
@@ -22,6 +79,28 @@ pub struct AppSettings {
     pub selected_source: Option<String>,
     pub game_directory: Option<String>,
     pub language: Option<String>,
+    #[serde(default = "default_keep_versions")]
+    pub keep_versions: usize,
+    #[serde(default)]
+    pub strict_updates: bool,
+    #[serde(default)]
+    pub extraction_limits: ExtractionLimits,
+    #[serde(default)]
+    pub validation: ValidationSettings,
+    #[serde(default)]
+    pub activate_after_install: bool,
+    #[serde(default)]
+    pub profiles: HashMap<String, Profile>,
+    #[serde(default)]
+    pub auto_update: AutoUpdateSettings,
+    #[serde(default)]
+    pub launch: LaunchSettings,
+    #[serde(default)]
+    pub wrapper: WrapperSettings,
+}
+
+fn default_keep_versions() -> usize {
+    DEFAULT_KEEP_VERSIONS
 }
 
 impl AppSettings {
@@ -32,6 +111,15 @@ impl AppSettings {
             selected_source: None,
             game_directory: None,
             language: None,
+            keep_versions: DEFAULT_KEEP_VERSIONS,
+            strict_updates: false,
+            extraction_limits: ExtractionLimits::default(),
+            validation: ValidationSettings::default(),
+            activate_after_install: false,
+            profiles: HashMap::new(),
+            auto_update: AutoUpdateSettings::default(),
+            launch: LaunchSettings::default(),
+            wrapper: WrapperSettings::default(),
         }
     }
 }
@@ -87,42 +175,122 @@ fn migrate_settings(settings: &mut AppSettings, defaults: &AppSettings) -> bool
 
 pub fn load_settings(app_handle: &tauri::AppHandle) -> Result<AppSettings, anyhow::Error> {
     let config_path = get_config_path(app_handle)?;
+    let defaults = load_bundled_defaults(app_handle)?;
+    load_settings_from(&config_path, defaults)
+}
 
+pub fn save_settings(
+    app_handle: &tauri::AppHandle,
+    settings: &AppSettings,
+) -> Result<(), anyhow::Error> {
+    let config_path = get_config_path(app_handle)?;
+    write_settings(&config_path, settings)
+}
+
+/// Path of the GUI's config file, resolved the way Tauri resolves
+/// `app_config_dir`.
+fn get_headless_config_path() -> Result<PathBuf, anyhow::Error> {
+    let config_dir =
+        dirs::config_dir().ok_or_else(|| anyhow::anyhow!("Failed to find config directory"))?;
+    Ok(config_dir.join(APP_IDENTIFIER).join("config.toml"))
+}
+
+/// Loads the GUI's settings without a running app, defaults are taken from
+/// the copy of default_config.toml built into the binary.
+pub fn load_headless_settings() -> Result<AppSettings, anyhow::Error> {
+    let defaults: AppSettings = toml::from_str(BUNDLED_DEFAULTS)?;
+    load_settings_from(&get_headless_config_path()?, defaults)
+}
+
+pub fn save_headless_settings(settings: &AppSettings) -> Result<(), anyhow::Error> {
+    write_settings(&get_headless_config_path()?, settings)
+}
+
+fn load_settings_from(
+    config_path: &Path,
+    defaults: AppSettings,
+) -> Result<AppSettings, anyhow::Error> {
     if let Some(parent_dir) = config_path.parent() {
         fs::create_dir_all(parent_dir)?;
     }
 
-    let defaults = load_bundled_defaults(app_handle)?;
-
     if !config_path.exists() {
         debug!(
             "Config file not found at {:?}. Using defaults.",
             config_path
         );
-        save_settings(app_handle, &defaults)?;
+        write_settings(config_path, &defaults)?;
         return Ok(defaults);
     }
 
-    let config_content = fs::read_to_string(&config_path)?;
+    let config_content = fs::read_to_string(config_path)?;
     let mut settings: AppSettings = toml::from_str(&config_content).unwrap_or_else(|e| {
         error!("Failed to parse config file: {}", e);
         defaults.clone()
     });
 
     if migrate_settings(&mut settings, &defaults) {
-        save_settings(app_handle, &settings)?;
+        write_settings(config_path, &settings)?;
     }
 
     Ok(settings)
 }
 
-pub fn save_settings(
-    app_handle: &tauri::AppHandle,
-    settings: &AppSettings,
-) -> Result<(), anyhow::Error> {
-    let config_path = get_config_path(app_handle)?;
+fn write_settings(config_path: &Path, settings: &AppSettings) -> Result<(), anyhow::Error> {
     let config_content = toml::to_string(settings)?;
-    fs::write(&config_path, config_content)?;
+    fs::write(config_path, config_content)?;
     debug!("Settings saved to: {:?}", config_path);
     Ok(())
 }
+
+/// Held by the GUI while it runs. It keeps settings and installed metadata
+/// in memory, so the CLI must not change them underneath it.
+pub struct GuiLock {
+    path: PathBuf,
+}
+
+impl Drop for GuiLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Writes the GUI's process id next to its config file.
+pub fn acquire_gui_lock(app_handle: &tauri::AppHandle) -> Result<GuiLock, anyhow::Error> {
+    let config_dir = app_handle.path().app_config_dir()?;
+    fs::create_dir_all(&config_dir)?;
+
+    let path = config_dir.join(GUI_LOCK_FILE_NAME);
+    fs::write(&path, std::process::id().to_string())?;
+    Ok(GuiLock { path })
+}
+
+/// Process id of a running GUI. A lock left behind by a GUI that did not
+/// exit cleanly is ignored.
+pub fn gui_lock_holder() -> Option<u32> {
+    let config_dir = get_headless_config_path().ok()?.parent()?.to_path_buf();
+    let pid: u32 = fs::read_to_string(config_dir.join(GUI_LOCK_FILE_NAME))
+        .ok()?
+        .trim()
+        .parse()
+        .ok()?;
+
+    let process_id = Pid::from_u32(pid);
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[process_id]),
+        true,
+        ProcessRefreshKind::nothing().with_exe(UpdateKind::OnlyIfNotSet),
+    );
+
+    // The id may have been reused by an unrelated process since
+    let current_exe = std::env::current_exe().ok();
+    system
+        .process(process_id)
+        .is_some_and(|process| {
+            process
+                .exe()
+                .is_none_or(|exe| Some(exe) == current_exe.as_deref())
+        })
+        .then_some(pid)
+}
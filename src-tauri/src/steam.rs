@@ -1,40 +1,13 @@
+use crate::vdf;
 use anyhow::{Context, Error};
+use log::warn;
+use serde::Serialize;
+#[cfg(not(target_os = "linux"))]
 use std::io;
-use std::path::PathBuf;
-use std::process::Command;
-use sysinfo::System;
+use std::path::{Path, PathBuf};
+use sysinfo::{Process, ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
 
-const LIMBUS_STEAM_ID: u32 = 1973530;
-
-#[cfg(target_os = "windows")]
-pub fn launch_game() -> Result<(), Error> {
-    if let Err(_) = Command::new("cmd")
-        .args(["/C", "start", &format!("steam://run/{}", LIMBUS_STEAM_ID)])
-        .spawn()
-    {
-        return Err(anyhow::anyhow!("Failed to launch Steam. Is it installed?"));
-    }
-
-    Ok(())
-}
-
-#[cfg(target_os = "linux")]
-pub fn launch_game() -> Result<(), Error> {
-    let result = Command::new("xdg-open")
-        .arg(format!("steam://run/{}", LIMBUS_STEAM_ID))
-        .spawn();
-
-    if let Err(_) = result {
-        if let Err(_) = Command::new("steam")
-            .args([format!("steam://run/{}", LIMBUS_STEAM_ID)])
-            .spawn()
-        {
-            return Err(anyhow::anyhow!("Failed to launch Steam. Is it installed?"));
-        }
-    }
-
-    Ok(())
-}
+pub const LIMBUS_STEAM_ID: u32 = 1973530;
 
 #[cfg(target_os = "windows")]
 fn get_steam_path() -> io::Result<PathBuf> {
@@ -71,24 +44,119 @@ fn get_steam_path() -> io::Result<PathBuf> {
     ))
 }
 
+// Flatpak app id of Steam, also used to launch it
+const FLATPAK_STEAM_ID: &str = "com.valvesoftware.Steam";
+
+/// Variables naming a Steam root, checked before the known locations.
 #[cfg(target_os = "linux")]
-fn get_steam_path() -> io::Result<PathBuf> {
-    let home = dirs::home_dir()
-        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "Home directory not found"))?;
-    let path = home.join(".steam/steam");
-    if path.exists() {
-        return Ok(path);
+const STEAM_PATH_VARIABLES: &[&str] = &["LLM_STEAM_PATH", "STEAM_COMPAT_CLIENT_INSTALL_PATH"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SteamKind {
+    Native,
+    Flatpak,
+    Snap,
+}
+
+#[derive(Clone, Debug)]
+pub struct SteamInstall {
+    pub path: PathBuf,
+    pub kind: SteamKind,
+}
+
+impl SteamInstall {
+    /// Command that starts this Steam, arguments for Steam are appended.
+    pub fn launcher(&self) -> Vec<String> {
+        match self.kind {
+            SteamKind::Native if cfg!(target_os = "windows") => {
+                vec![self.path.join("steam.exe").to_string_lossy().to_string()]
+            }
+            SteamKind::Native => vec!["steam".to_string()],
+            SteamKind::Flatpak => vec![
+                "flatpak".to_string(),
+                "run".to_string(),
+                FLATPAK_STEAM_ID.to_string(),
+            ],
+            SteamKind::Snap => vec!["snap".to_string(), "run".to_string(), "steam".to_string()],
+        }
     }
+}
 
-    let path = home.join(".local/share/Steam");
-    if path.exists() {
-        return Ok(path);
+#[cfg(target_os = "linux")]
+fn steam_kind(path: &Path) -> SteamKind {
+    let path = path.to_string_lossy();
+
+    if path.contains(&format!(".var/app/{}", FLATPAK_STEAM_ID)) {
+        SteamKind::Flatpak
+    } else if path.contains("snap/steam") {
+        SteamKind::Snap
+    } else {
+        SteamKind::Native
+    }
+}
+
+/// Every Steam root that exists, overrides first. The same root reached
+/// through a symlink is listed once.
+#[cfg(target_os = "linux")]
+pub fn get_steam_installs() -> Vec<SteamInstall> {
+    let mut candidates: Vec<PathBuf> = STEAM_PATH_VARIABLES
+        .iter()
+        .filter_map(std::env::var_os)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+        .collect();
+
+    if let Some(home) = dirs::home_dir() {
+        let flatpak_home = home.join(".var/app").join(FLATPAK_STEAM_ID);
+
+        candidates.extend([
+            home.join(".steam/steam"),
+            home.join(".steam/root"),
+            home.join(".local/share/Steam"),
+            flatpak_home.join(".local/share/Steam"),
+            flatpak_home.join("data/Steam"),
+            flatpak_home.join(".steam/steam"),
+            home.join("snap/steam/common/.local/share/Steam"),
+            home.join("snap/steam/common/.steam/steam"),
+        ]);
     }
 
-    Err(io::Error::new(
-        io::ErrorKind::NotFound,
-        "Steam installation not found",
-    ))
+    if let Some(data_dir) = dirs::data_dir() {
+        candidates.push(data_dir.join("Steam"));
+    }
+
+    let mut seen = Vec::new();
+    let mut installs = Vec::new();
+
+    for path in candidates {
+        let Ok(resolved) = path.canonicalize() else {
+            continue;
+        };
+
+        if !resolved.join("steamapps").is_dir() || seen.contains(&resolved) {
+            continue;
+        }
+
+        seen.push(resolved.clone());
+        installs.push(SteamInstall {
+            kind: steam_kind(&resolved),
+            path,
+        });
+    }
+
+    installs
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn get_steam_installs() -> Vec<SteamInstall> {
+    get_steam_path()
+        .map(|path| {
+            vec![SteamInstall {
+                path,
+                kind: SteamKind::Native,
+            }]
+        })
+        .unwrap_or_default()
 }
 
 pub fn validate_game_directory(directory: &str) -> Result<(), Error> {
@@ -109,58 +177,330 @@ pub fn validate_game_directory(directory: &str) -> Result<(), Error> {
     Ok(())
 }
 
-pub fn get_game_directory() -> Result<PathBuf, Error> {
-    let steam_path = get_steam_path()?;
-    let default_path = steam_path.join("steamapps").join("common");
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LibraryFolder {
+    pub path: PathBuf,  // Library root, holds `steamapps`
+    pub apps: Vec<u32>, // Installed app ids, empty in the old format
+}
 
-    let vdf_path = steam_path.join("steamapps").join("libraryfolders.vdf");
-    if !vdf_path.exists() {
-        return Err(anyhow::anyhow!("Steam libraries file not found"));
-    }
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct AppManifest {
+    pub installdir: String,        // Folder name in steamapps/common
+    pub buildid: Option<String>,   // Changes with every game update
+    pub state_flags: u32,          // 4 when fully installed
+    pub last_updated: Option<u64>, // Unix time of the last update
+}
 
-    let vdf_content = std::fs::read_to_string(vdf_path)
-        .with_context(|| format!("Failed to read Steam libraries file"))?;
+/// Library roots listed in `libraryfolders.vdf`, both the current format
+/// with an `apps` map and the old one with bare paths.
+pub fn parse_library_folders(content: &str) -> Result<Vec<LibraryFolder>, Error> {
+    let root = vdf::parse(content).with_context(|| "Failed to parse Steam libraries file")?;
+    let folders = root
+        .get("libraryfolders")
+        .ok_or_else(|| anyhow::anyhow!("Steam libraries file has no libraryfolders"))?;
 
-    let mut library_paths = vec![default_path];
-    for line in vdf_content.lines() {
-        if line.contains("\"path\"") {
-            if let Some(path_str) = line.split('"').nth(3) {
-                let lib_path = PathBuf::from(path_str).join("steamapps").join("common");
-                library_paths.push(lib_path);
-            }
+    let mut libraries = Vec::new();
+    for (key, value) in folders.entries() {
+        if key.parse::<u32>().is_err() {
+            continue;
         }
+
+        let (path, apps) = match value {
+            vdf::Value::String(path) => (path.as_str(), Vec::new()),
+            vdf::Value::Object(_) => {
+                let Some(path) = value.get_str("path") else {
+                    continue;
+                };
+                let apps = value
+                    .get("apps")
+                    .map(|apps| {
+                        apps.entries()
+                            .iter()
+                            .filter_map(|(id, _)| id.parse().ok())
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                (path, apps)
+            }
+        };
+
+        libraries.push(LibraryFolder {
+            path: PathBuf::from(path),
+            apps,
+        });
     }
 
-    for library in library_paths {
-        let parent = library
-            .parent()
-            .ok_or(anyhow::anyhow!("Invalid library path"))?;
-        let manifest_path = parent.join(format!("appmanifest_{}.acf", LIMBUS_STEAM_ID));
+    Ok(libraries)
+}
+
+pub fn parse_app_manifest(content: &str) -> Result<AppManifest, Error> {
+    let root = vdf::parse(content).with_context(|| "Failed to parse game manifest")?;
+    let app_state = root
+        .get("AppState")
+        .ok_or_else(|| anyhow::anyhow!("Game manifest has no AppState"))?;
 
-        if manifest_path.exists() {
-            let manifest = std::fs::read_to_string(manifest_path)
-                .with_context(|| format!("Failed to read game manifest"))?;
+    Ok(AppManifest {
+        installdir: app_state
+            .get_str("installdir")
+            .ok_or_else(|| anyhow::anyhow!("Game manifest has no installdir"))?
+            .to_string(),
+        buildid: app_state.get_str("buildid").map(str::to_string),
+        state_flags: app_state
+            .get_str("StateFlags")
+            .and_then(|flags| flags.parse().ok())
+            .unwrap_or_default(),
+        last_updated: app_state
+            .get_str("LastUpdated")
+            .and_then(|time| time.parse().ok()),
+    })
+}
 
-            for line in manifest.lines() {
-                if line.contains("\"installdir\"") {
-                    if let Some(dir_name) = line.split('"').nth(3) {
-                        return Ok(library.join(dir_name));
-                    }
-                }
+fn read_app_manifest(manifest_path: &Path) -> Result<AppManifest, Error> {
+    let manifest = std::fs::read_to_string(manifest_path)
+        .with_context(|| format!("Failed to read game manifest"))?;
+    parse_app_manifest(&manifest)
+}
+
+/// Manifest of the game installed at `game_path`, which sits in
+/// `steamapps/common` next to it.
+pub fn read_game_manifest(game_path: &Path) -> Result<AppManifest, Error> {
+    let steamapps_path = game_path
+        .parent()
+        .and_then(Path::parent)
+        .ok_or_else(|| anyhow::anyhow!("Game directory is not in a Steam library"))?;
+
+    read_app_manifest(&steamapps_path.join(format!("appmanifest_{}.acf", LIMBUS_STEAM_ID)))
+}
+
+/// Current build id of the game at `game_path`, `None` when Steam's manifest
+/// cannot be read.
+pub fn read_game_build(game_path: &Path) -> Option<String> {
+    read_game_manifest(game_path).ok()?.buildid
+}
+
+/// Libraries of a Steam install, ones that list the game first. The
+/// install folder is a library even when the file does not mention it.
+fn library_folders(install: &SteamInstall) -> Result<Vec<LibraryFolder>, Error> {
+    let vdf_path = install.path.join("steamapps").join("libraryfolders.vdf");
+
+    let mut libraries = if vdf_path.exists() {
+        let vdf_content = std::fs::read_to_string(vdf_path)
+            .with_context(|| format!("Failed to read Steam libraries file"))?;
+        parse_library_folders(&vdf_content)?
+    } else {
+        Vec::new()
+    };
+
+    if !libraries.iter().any(|library| library.path == install.path) {
+        libraries.push(LibraryFolder {
+            path: install.path.clone(),
+            apps: Vec::new(),
+        });
+    }
+
+    libraries.sort_by_key(|library| !library.apps.contains(&LIMBUS_STEAM_ID));
+    Ok(libraries)
+}
+
+/// Looks through the libraries of every Steam install and returns the game
+/// directory with the install whose library holds it.
+pub fn find_game() -> Result<(PathBuf, SteamInstall), Error> {
+    let installs = get_steam_installs();
+    if installs.is_empty() {
+        return Err(anyhow::anyhow!("Steam installation not found"));
+    }
+
+    let mut searched = Vec::new();
+
+    for install in &installs {
+        // One broken install should not hide the game in another
+        let libraries = match library_folders(install) {
+            Ok(libraries) => libraries,
+            Err(e) => {
+                warn!("Skipping Steam install {:?}: {:?}", install.path, e);
+                continue;
+            }
+        };
+
+        for library in libraries {
+            if searched.contains(&library.path) {
+                continue;
+            }
+            searched.push(library.path.clone());
+
+            let steamapps_path = library.path.join("steamapps");
+            let manifest_path = steamapps_path.join(format!("appmanifest_{}.acf", LIMBUS_STEAM_ID));
+            if !manifest_path.exists() {
+                continue;
             }
+
+            let manifest = read_app_manifest(&manifest_path)?;
+            let game_path = steamapps_path.join("common").join(manifest.installdir);
+            return Ok((game_path, install.clone()));
         }
     }
 
     Err(anyhow::anyhow!("Limbus not found in any Steam library"))
 }
 
-pub fn is_game_running() -> bool {
-    let system = System::new_all();
+pub fn get_game_directory() -> Result<PathBuf, Error> {
+    find_game().map(|(game_path, _)| game_path)
+}
+
+/// The Steam install that has the game, or the first one found.
+pub fn game_steam_install() -> Option<SteamInstall> {
+    find_game()
+        .map(|(_, install)| install)
+        .ok()
+        .or_else(|| get_steam_installs().into_iter().next())
+}
 
-    system.processes().iter().any(|(_, process)| {
-        process
-            .name()
-            .to_str()
-            .map_or(false, |name| name.starts_with("LimbusCompany.e"))
-    })
+/// Keeps the process list between checks and refreshes only the fields
+/// needed to recognize the game.
+pub struct GameMonitor {
+    system: System,
+}
+
+impl GameMonitor {
+    pub fn new() -> Self {
+        Self {
+            system: System::new(),
+        }
+    }
+
+    /// Whether the game is running, from `game_path` if given.
+    pub fn is_game_running(&mut self, game_path: Option<&Path>) -> bool {
+        self.system.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::nothing()
+                .with_exe(UpdateKind::OnlyIfNotSet)
+                .with_cmd(UpdateKind::OnlyIfNotSet),
+        );
+
+        let game_dir = game_path.map(normalize_path);
+        self.system
+            .processes()
+            .values()
+            .any(|process| is_game_process(process, game_dir.as_deref()))
+    }
+}
+
+pub fn is_game_running(game_path: Option<&Path>) -> bool {
+    GameMonitor::new().is_game_running(game_path)
+}
+
+fn is_game_process(process: &Process, game_dir: Option<&str>) -> bool {
+    let name_matches = process
+        .name()
+        .to_str()
+        .is_some_and(|name| name.starts_with("LimbusCompany.e"));
+
+    let Some(game_dir) = game_dir.filter(|_| name_matches) else {
+        return name_matches;
+    };
+
+    let in_game_dir = |path: &Path| {
+        normalize_path(path)
+            .strip_prefix(game_dir)
+            .is_some_and(|rest| rest.starts_with('/'))
+    };
+
+    // Under Wine and Proton the executable is the loader, the game path
+    // shows up in the command line instead
+    let exe = process.exe();
+    let cmd = process.cmd();
+    if exe.is_none() && cmd.is_empty() {
+        // Details of processes of other users are not readable
+        return true;
+    }
+
+    exe.is_some_and(in_game_dir) || cmd.iter().any(|arg| in_game_dir(Path::new(arg)))
+}
+
+/// Forward slashes, no Windows verbatim or Wine `Z:` drive prefix and, on
+/// Windows, lowercase, so paths from different sources compare equal.
+fn normalize_path(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let path = path.strip_prefix("//?/").unwrap_or(&path);
+
+    #[cfg(target_os = "windows")]
+    let path = path.to_lowercase();
+    #[cfg(not(target_os = "windows"))]
+    let path = path
+        .strip_prefix("Z:")
+        .or_else(|| path.strip_prefix("z:"))
+        .unwrap_or(path)
+        .to_string();
+
+    path.trim_end_matches('/').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_libraries_and_their_apps() {
+        let libraries = parse_library_folders(
+            r#"
+            "libraryfolders"
+            {
+                "0"
+                {
+                    "path"      "C:\\Program Files (x86)\\Steam"
+                    "apps"      { "228980" "123" }
+                }
+                "1"
+                {
+                    "path"      "D:\\SteamLibrary"
+                    "apps"      { "1973530" "456" }
+                }
+            }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(libraries.len(), 2);
+        assert_eq!(libraries[1].path, PathBuf::from(r"D:\SteamLibrary"));
+        assert_eq!(libraries[1].apps, vec![LIMBUS_STEAM_ID]);
+    }
+
+    #[test]
+    fn reads_old_library_format() {
+        let libraries = parse_library_folders(
+            r#""LibraryFolders" { "TimeNextStatsReport" "1" "1" "/mnt/games" }"#,
+        )
+        .unwrap();
+
+        assert_eq!(libraries.len(), 1);
+        assert_eq!(libraries[0].path, PathBuf::from("/mnt/games"));
+    }
+
+    #[test]
+    fn reads_app_manifest_fields() {
+        let manifest = parse_app_manifest(
+            r#"
+            "AppState"
+            {
+                "appid"         "1973530"
+                "StateFlags"    "4"
+                "installdir"    "Limbus Company"
+                "LastUpdated"   "1718000000"
+                "buildid"       "14567890"
+            }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            manifest,
+            AppManifest {
+                installdir: "Limbus Company".to_string(),
+                buildid: Some("14567890".to_string()),
+                state_flags: 4,
+                last_updated: Some(1718000000),
+            }
+        );
+    }
 }
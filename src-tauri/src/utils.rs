@@ -1,22 +1,49 @@
+use crate::archive::{self, ArchiveType, ExtractionLimits};
+use crate::composite::{self, CompositeLocalization};
+use crate::overlay::{self, OverlayBase};
+use crate::validation::{self, ValidationIssue, ValidationSettings};
 use anyhow::Context;
 use futures::stream::StreamExt;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
 use log::{debug, info, warn};
 use md5::{Digest, Md5};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::{
     fs,
-    io::{self, BufReader, Read, Write},
+    io::{BufReader, Read, Write},
     path::{Path, PathBuf},
     time::Duration,
 };
 use tempfile::Builder;
-use zip::ZipArchive;
 
 const METADATA_FILE_NAME: &str = "llc_config.toml";
+const ARCHIVE_CACHE_DIR_NAME: &str = "ArchiveCache";
 const REPO_NAME: &str = "kimght/LimbusLocalizationManager";
 
+// Source recorded for localizations installed from a local archive or folder
+pub const LOCAL_SOURCE: &str = "local";
+const DEV_VERSION: &str = "dev";
+
+// Languages the game ships with, they have no folder in Lang/
+pub const BUILTIN_LANGUAGES: &[&str] = &["kr", "en", "jp"];
+
+// Folders and JSON files found in every language folder of the game
+const LANGUAGE_MARKER_DIRECTORIES: &[&str] = &[
+    "StoryData",
+    "BattleAnnouncerDlg",
+    "PersonalityVoiceDlg",
+    "EGOVoiceDig",
+];
+const LANGUAGE_MARKER_FILES: &[&str] = &[
+    "BattleKeywords",
+    "Bufs",
+    "Passives",
+    "Personalities",
+    "Skills",
+];
+
 static HTTP_CLIENT: std::sync::LazyLock<Client> = std::sync::LazyLock::new(|| {
     Client::builder()
         .user_agent("Limbus Launcher")
@@ -44,6 +71,8 @@ pub enum Format {
     New, // just contents of LANG folder
     #[serde(rename = "auto")]
     Auto, // Find the first folder with StoryData
+    #[serde(rename = "overlay")]
+    Overlay(OverlayBase), // Files or JSON keys applied on top of another localization
     #[serde(untagged)]
     Unknown(String),
 }
@@ -55,6 +84,21 @@ pub struct Font {
     pub name: String, // Filename in Font/ folder
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Component {
+    pub id: String,         // Unique within the localization
+    pub name: String,       // Human readable name
+    pub paths: Vec<String>, // Globs relative to the language folder, e.g. "StoryData/**"
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SubPack {
+    pub id: String,   // Installed as Lang/<id>
+    pub name: String, // Human readable name
+    pub flag: String, // Country code for flag
+    pub path: String, // Language folder inside the archive
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Localization {
     pub id: String,           // Unique identifier
@@ -64,10 +108,126 @@ pub struct Localization {
     pub icon: String,         // Icon url of the localization
     pub description: String,  // Description in markdown
     pub authors: Vec<String>, // List of authors
-    pub url: String,          // Url to zip archive
-    pub size: u64,            // Size of the zip archive to check integrity
+    pub url: String,          // Url to archive
+    pub size: u64,            // Size of the archive to check integrity
+    #[serde(default)]
+    pub hash: Option<String>, // Md5 hash of the archive, checked if present
     pub fonts: Vec<Font>,     // List of fonts to install
     pub format: Format,
+    #[serde(default)]
+    pub archive_type: Option<ArchiveType>, // Hint in case magic bytes are not recognized
+    #[serde(default)]
+    pub components: Vec<Component>, // Optional parts that can be installed separately
+    #[serde(default)]
+    pub packs: Vec<SubPack>, // Language folders shipped in the same archive
+    #[serde(default)]
+    pub path: Option<String>, // Language folder inside the archive, skips format detection
+    #[serde(default)]
+    pub game_builds: Vec<String>, // Steam build ids this version is known to work with
+}
+
+impl Localization {
+    /// Minimal manifest entry for an archive or folder on disk.
+    pub fn local(id: &str, name: &str, format: Format, path: &Path) -> Localization {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+
+        Localization {
+            id: id.to_string(),
+            version: format!("local-{}", timestamp),
+            name: name.to_string(),
+            flag: String::new(),
+            icon: String::new(),
+            description: String::new(),
+            authors: Vec::new(),
+            url: path.to_string_lossy().to_string(),
+            size: fs::metadata(path).map(|m| m.len()).unwrap_or_default(),
+            hash: None,
+            fonts: Vec::new(),
+            format,
+            archive_type: None,
+            components: Vec::new(),
+            packs: Vec::new(),
+            path: None,
+            game_builds: Vec::new(),
+        }
+    }
+
+    /// Turns a sub-pack into a standalone localization sharing the archive.
+    pub fn sub_pack(&self, pack: &SubPack) -> Localization {
+        Localization {
+            id: pack.id.clone(),
+            name: pack.name.clone(),
+            flag: pack.flag.clone(),
+            packs: Vec::new(),
+            path: Some(pack.path.clone()),
+            ..self.clone()
+        }
+    }
+
+    /// Finds the localization or sub-pack installed under `id`.
+    pub fn resolve(&self, id: &str) -> Option<Localization> {
+        if self.id == id && self.packs.is_empty() {
+            return Some(self.clone());
+        }
+
+        self.packs
+            .iter()
+            .find(|pack| pack.id == id)
+            .map(|pack| self.sub_pack(pack))
+    }
+
+    /// Localizations to install for the selected sub-packs, all of them if
+    /// nothing was selected.
+    pub fn selected_packs(
+        &self,
+        packs: Option<&[String]>,
+    ) -> Result<Vec<Localization>, anyhow::Error> {
+        if self.packs.is_empty() {
+            return Ok(vec![self.clone()]);
+        }
+
+        let Some(selected) = packs else {
+            return Ok(self.packs.iter().map(|pack| self.sub_pack(pack)).collect());
+        };
+
+        let selected = selected
+            .iter()
+            .map(|id| {
+                self.packs
+                    .iter()
+                    .find(|pack| &pack.id == id)
+                    .map(|pack| self.sub_pack(pack))
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("Localization '{}' has no sub-pack '{}'", self.id, id)
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if selected.is_empty() {
+            return Err(anyhow::anyhow!(
+                "No sub-packs of localization '{}' were selected",
+                self.id
+            ));
+        }
+
+        Ok(selected)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LocalizationSnapshot {
+    pub version: String,            // Version of the cached archive
+    pub archive: String,            // Filename in ArchiveCache/<id>/ folder
+    pub localization: Localization, // Manifest entry the archive was installed from
+}
+
+#[derive(Clone, Debug)]
+pub struct InstallReport {
+    pub snapshot: LocalizationSnapshot,
+    pub issues: Vec<ValidationIssue>, // Problems found in the installed JSON files
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -75,12 +235,37 @@ pub struct InstalledLocalization {
     pub id: String,
     pub version: String,
     pub source: String,
+    #[serde(default)]
+    pub pinned: bool, // Skipped by update and play, set on rollback
+    #[serde(default)]
+    pub snapshots: Vec<LocalizationSnapshot>, // Newest first, including the installed one
+    #[serde(default)]
+    pub components: Option<Vec<String>>, // Selected component ids, everything if not set
+    #[serde(default)]
+    pub dev_folder: Option<String>, // Working folder mirrored in dev mode, never updated
+    #[serde(default)]
+    pub game_build: Option<String>, // Steam build id of the game when installed
+}
+
+impl InstalledLocalization {
+    pub fn overlay_base(&self) -> Option<&OverlayBase> {
+        match &self.snapshots.first()?.localization.format {
+            Format::Overlay(base) => Some(base),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct InstalledMetadata {
     pub format_version: u32,
     pub installed: HashMap<String, InstalledLocalization>,
+    #[serde(default)]
+    pub composites: HashMap<String, CompositeLocalization>, // Built locally from installed ones
+    #[serde(default)]
+    pub stashed: HashMap<String, InstalledLocalization>, // Removed by a profile, archives kept
+    #[serde(default)]
+    pub game_build: Option<String>, // Steam build id of the game seen last
 }
 
 impl InstalledMetadata {
@@ -88,22 +273,186 @@ impl InstalledMetadata {
         Self {
             format_version: 1,
             installed: HashMap::new(),
+            composites: HashMap::new(),
+            stashed: HashMap::new(),
+            game_build: None,
         }
     }
 
-    pub fn with_localization(localization: &Localization, source: &String) -> Self {
-        let mut installed_metadata = Self::new();
+    /// Records a freshly installed version and returns the snapshots that no
+    /// longer fit into `keep_versions` or were replaced by this install, so
+    /// their archives can be removed. The installed version is always kept,
+    /// overlays are rebuilt from it.
+    pub fn record_install(
+        &mut self,
+        localization: &Localization,
+        source: &str,
+        snapshot: LocalizationSnapshot,
+        components: Option<Vec<String>>,
+        game_build: Option<String>,
+        keep_versions: usize,
+    ) -> Vec<LocalizationSnapshot> {
+        if let Some(stashed) = self.stashed.remove(&localization.id) {
+            self.installed
+                .entry(localization.id.clone())
+                .or_insert(stashed);
+        }
 
-        installed_metadata.installed.insert(
-            localization.id.clone(),
-            InstalledLocalization {
+        let installed = self
+            .installed
+            .entry(localization.id.clone())
+            .or_insert_with(|| InstalledLocalization {
                 id: localization.id.clone(),
                 version: localization.version.clone(),
-                source: source.clone(),
+                source: source.to_string(),
+                pinned: false,
+                snapshots: Vec::new(),
+                components: None,
+                dev_folder: None,
+                game_build: None,
+            });
+
+        installed.version = localization.version.clone();
+        installed.source = source.to_string();
+        installed.pinned = false;
+        installed.components = components;
+        installed.dev_folder = None;
+        installed.game_build = game_build;
+
+        // A reinstalled version may have been cached under another name
+        let mut evicted = Vec::new();
+        installed.snapshots.retain(|s| {
+            if s.version != snapshot.version {
+                return true;
+            }
+            if s.archive != snapshot.archive {
+                evicted.push(s.clone());
+            }
+            false
+        });
+        installed.snapshots.insert(0, snapshot);
+
+        let keep_versions = keep_versions.max(1);
+        if installed.snapshots.len() > keep_versions {
+            evicted.extend(installed.snapshots.split_off(keep_versions));
+        }
+        evicted
+    }
+
+    /// Finds a cached archive of `version`, or the newest one, among
+    /// installed and stashed localizations.
+    pub fn cached_snapshot(
+        &self,
+        localization_id: &str,
+        version: Option<&str>,
+    ) -> Option<(&InstalledLocalization, &LocalizationSnapshot)> {
+        [
+            self.installed.get(localization_id),
+            self.stashed.get(localization_id),
+        ]
+        .into_iter()
+        .flatten()
+        .find_map(|installed| {
+            installed
+                .snapshots
+                .iter()
+                .find(|snapshot| version.map_or(true, |version| snapshot.version == version))
+                .map(|snapshot| (installed, snapshot))
+        })
+    }
+
+    /// Ids of installed overlays built directly on top of `base_id`.
+    pub fn overlays_on(&self, base_id: &str) -> Vec<String> {
+        let mut overlay_ids: Vec<String> = self
+            .installed
+            .values()
+            .filter(|installed| {
+                installed
+                    .overlay_base()
+                    .is_some_and(|base| base.id == base_id)
+            })
+            .map(|installed| installed.id.clone())
+            .collect();
+        overlay_ids.sort();
+        overlay_ids
+    }
+
+    /// Overlays that need `base_id`, directly or through another overlay.
+    /// Each comes after the overlay it is built on, so they can be reapplied
+    /// in order.
+    pub fn dependent_overlays(&self, base_id: &str) -> Vec<String> {
+        let mut dependents = self.overlays_on(base_id);
+        let mut next = 0;
+
+        while next < dependents.len() {
+            for overlay_id in self.overlays_on(&dependents[next]) {
+                if overlay_id != base_id && !dependents.contains(&overlay_id) {
+                    dependents.push(overlay_id);
+                }
+            }
+            next += 1;
+        }
+
+        dependents
+    }
+
+    /// Manifest entries of the dependent overlays, for uninstalling them.
+    pub fn dependent_overlay_localizations(&self, base_id: &str) -> Vec<Localization> {
+        self.dependent_overlays(base_id)
+            .iter()
+            .filter_map(|id| self.installed.get(id))
+            .filter_map(|installed| installed.snapshots.first())
+            .map(|snapshot| snapshot.localization.clone())
+            .collect()
+    }
+
+    /// Whether a composite reads from `localization_id`, directly or through
+    /// other composites.
+    pub fn composite_reads_from(&self, composite_id: &str, localization_id: &str) -> bool {
+        let mut visited: Vec<&str> = Vec::new();
+        let mut pending = vec![composite_id];
+
+        while let Some(id) = pending.pop() {
+            let Some(composite) = self.composites.get(id) else {
+                continue;
+            };
+            if visited.contains(&id) {
+                continue;
+            }
+            visited.push(id);
+
+            for input in composite.inputs() {
+                if input == localization_id {
+                    return true;
+                }
+                pending.push(input);
+            }
+        }
+
+        false
+    }
+
+    pub fn is_dev_folder(&self, localization_id: &str) -> bool {
+        self.installed
+            .get(localization_id)
+            .is_some_and(|installed| installed.dev_folder.is_some())
+    }
+
+    /// Marks `Lang/<id>` as mirrored from a working folder.
+    pub fn record_dev_folder(&mut self, localization_id: &str, folder: &Path) {
+        self.installed.insert(
+            localization_id.to_string(),
+            InstalledLocalization {
+                id: localization_id.to_string(),
+                version: DEV_VERSION.to_string(),
+                source: LOCAL_SOURCE.to_string(),
+                pinned: false,
+                snapshots: Vec::new(),
+                components: None,
+                dev_folder: Some(folder.to_string_lossy().to_string()),
+                game_build: None,
             },
         );
-
-        installed_metadata
     }
 }
 
@@ -284,26 +633,441 @@ pub async fn install_fonts_for_localization(
 
 pub async fn install_localization(
     game_path: &PathBuf,
+    metadata: &InstalledMetadata,
     localization: &Localization,
-) -> Result<(), anyhow::Error> {
+    components: Option<&[String]>,
+    limits: &ExtractionLimits,
+    validation: &ValidationSettings,
+) -> Result<InstallReport, anyhow::Error> {
     let temp_dir = create_temp_directory(&localization.id)?;
-    let extract_path = temp_dir.path();
-
     let download_path = download_localization_file(&localization, &temp_dir).await?;
 
-    debug!("Extracting localization to: {:?}", extract_path);
-    extract_zip_archive(&download_path, extract_path)?;
-
-    let language_dir = find_language_directory(extract_path, &localization.format)?;
-    install_to_game_directory(&game_path, &language_dir, &localization)?;
+    let issues = install_localization_archive(
+        game_path,
+        metadata,
+        &download_path,
+        localization,
+        components,
+        limits,
+        validation,
+    )?;
+    let snapshot = cache_localization_archive(game_path, localization, &download_path)?;
 
     info!(
         "Successfully installed localization '{}' version '{}'",
         localization.id, localization.version
     );
+    Ok(InstallReport { snapshot, issues })
+}
+
+/// Downloads the archive once and installs every target from it, with
+/// fonts. Targets are sub-packs of one bundle or a single localization, each
+/// with its selected components. An error means nothing was installed,
+/// otherwise there is one result per target, in order.
+pub async fn install_shared_archive(
+    game_path: &PathBuf,
+    metadata: &InstalledMetadata,
+    targets: &[(Localization, Option<Vec<String>>)],
+    limits: &ExtractionLimits,
+    validation: &ValidationSettings,
+) -> Result<Vec<Result<InstallReport, anyhow::Error>>, anyhow::Error> {
+    let Some((first, _)) = targets.first() else {
+        return Ok(Vec::new());
+    };
+
+    let temp_dir = create_temp_directory(&first.id)?;
+    let download_path = download_localization_file(first, &temp_dir).await?;
+
+    let mut shared_snapshot: Option<LocalizationSnapshot> = None;
+    let mut results = Vec::new();
+
+    for (localization, components) in targets {
+        let issues = match install_localization_archive(
+            game_path,
+            metadata,
+            &download_path,
+            localization,
+            components.as_deref(),
+            limits,
+            validation,
+        ) {
+            Ok(issues) => issues,
+            Err(e) => {
+                results.push(Err(e.context(format!(
+                    "Failed to install localization '{}'",
+                    localization.id
+                ))));
+                continue;
+            }
+        };
+
+        let result = install_fonts_for_localization(game_path, localization)
+            .await
+            .with_context(|| format!("Failed to install fonts for localization"))
+            .and_then(|()| {
+                let snapshot = match &shared_snapshot {
+                    Some(shared) => share_cached_archive(game_path, localization, shared)?,
+                    None => cache_localization_archive(game_path, localization, &download_path)?,
+                };
+                Ok(InstallReport { snapshot, issues })
+            })
+            .with_context(|| format!("Failed to install localization '{}'", localization.id));
+
+        if let Ok(report) = &result {
+            info!(
+                "Successfully installed localization '{}' version '{}'",
+                localization.id, localization.version
+            );
+            shared_snapshot.get_or_insert_with(|| report.snapshot.clone());
+        }
+        results.push(result);
+    }
+
+    Ok(results)
+}
+
+/// Installs from an archive or folder on disk, folders are packed first so
+/// the result can be cached like a downloaded archive.
+pub fn install_local_localization(
+    game_path: &PathBuf,
+    metadata: &InstalledMetadata,
+    source_path: &Path,
+    localization: &Localization,
+    limits: &ExtractionLimits,
+    validation: &ValidationSettings,
+) -> Result<InstallReport, anyhow::Error> {
+    let temp_dir = create_temp_directory(&localization.id)?;
+
+    let archive_path = if source_path.is_dir() {
+        let archive_path = temp_dir.path().join("localization.archive");
+        debug!("Packing {:?} into {:?}", source_path, archive_path);
+        archive::create_zip_archive(source_path, &archive_path, &|_| true)?;
+        archive_path
+    } else if source_path.is_file() {
+        source_path.to_path_buf()
+    } else {
+        return Err(anyhow::anyhow!("{:?} does not exist", source_path));
+    };
+
+    let issues = install_localization_archive(
+        game_path,
+        metadata,
+        &archive_path,
+        localization,
+        None,
+        limits,
+        validation,
+    )?;
+    let snapshot = cache_localization_archive(game_path, localization, &archive_path)?;
+
+    info!(
+        "Successfully installed local localization '{}' from {:?}",
+        localization.id, source_path
+    );
+    Ok(InstallReport { snapshot, issues })
+}
+
+/// Packs `Lang/<id>` into a `new` format zip and returns a manifest entry
+/// for it, also written next to the archive as `<archive name>.json`.
+/// Without fonts in the archive, the entry lists the fonts the installed
+/// version was downloaded with.
+pub fn export_localization(
+    game_path: &PathBuf,
+    localization_id: &str,
+    installed: Option<&InstalledLocalization>,
+    destination: &Path,
+    include_fonts: bool,
+) -> Result<Localization, anyhow::Error> {
+    let source_path = game_path
+        .join("LimbusCompany_Data")
+        .join("Lang")
+        .join(localization_id);
+
+    if !source_path.is_dir() {
+        return Err(anyhow::anyhow!(
+            "Localization '{}' is not installed",
+            localization_id
+        ));
+    }
+
+    info!(
+        "Exporting localization '{}' to {:?}",
+        localization_id, destination
+    );
+    archive::create_zip_archive(&source_path, destination, &|relative| {
+        include_fonts || !relative.starts_with("Font")
+    })?;
+
+    let mut localization = installed
+        .and_then(|installed| installed.snapshots.first())
+        .map(|snapshot| snapshot.localization.clone())
+        .unwrap_or_else(|| {
+            Localization::local(localization_id, localization_id, Format::New, destination)
+        });
+
+    localization.url = String::new();
+    localization.size = fs::metadata(destination)
+        .with_context(|| format!("Failed to get size of {:?}", destination))?
+        .len();
+    localization.hash = Some(calculate_md5(destination)?);
+    localization.format = Format::New;
+    localization.archive_type = Some(ArchiveType::Zip);
+    localization.packs = Vec::new();
+    localization.path = None;
+    if include_fonts {
+        localization.fonts = Vec::new();
+    }
+    if let Some(installed) = installed {
+        localization.version = installed.version.clone();
+    }
+
+    // Appended rather than swapped in, so `foo.json` is never overwritten
+    let mut manifest_name = destination
+        .file_name()
+        .ok_or_else(|| anyhow::anyhow!("Invalid export destination {:?}", destination))?
+        .to_os_string();
+    manifest_name.push(".json");
+    let manifest_path = destination.with_file_name(manifest_name);
+    let manifest = serde_json::to_string_pretty(&localization)
+        .with_context(|| format!("Failed to serialize manifest entry"))?;
+    fs::write(&manifest_path, manifest)
+        .with_context(|| format!("Failed to write manifest entry {:?}", manifest_path))?;
+
+    Ok(localization)
+}
+
+pub fn restore_localization_snapshot(
+    game_path: &PathBuf,
+    metadata: &InstalledMetadata,
+    snapshot: &LocalizationSnapshot,
+    components: Option<&[String]>,
+    limits: &ExtractionLimits,
+    validation: &ValidationSettings,
+) -> Result<Vec<ValidationIssue>, anyhow::Error> {
+    let archive_path =
+        archive_cache_directory(game_path, &snapshot.localization.id).join(&snapshot.archive);
+
+    if !archive_path.is_file() {
+        return Err(anyhow::anyhow!(
+            "Cached archive for '{}' version '{}' not found",
+            snapshot.localization.id,
+            snapshot.version
+        ));
+    }
+
+    let issues = install_localization_archive(
+        game_path,
+        metadata,
+        &archive_path,
+        &snapshot.localization,
+        components,
+        limits,
+        validation,
+    )?;
+
+    info!(
+        "Successfully restored localization '{}' version '{}'",
+        snapshot.localization.id, snapshot.version
+    );
+    Ok(issues)
+}
+
+/// Rebuilds an installed overlay from its cached archive on top of the
+/// base recorded in `metadata`.
+pub fn reapply_overlay(
+    game_path: &PathBuf,
+    metadata: &InstalledMetadata,
+    overlay_id: &str,
+    limits: &ExtractionLimits,
+    validation: &ValidationSettings,
+) -> Result<(), anyhow::Error> {
+    let installed = metadata
+        .installed
+        .get(overlay_id)
+        .ok_or_else(|| anyhow::anyhow!("Overlay '{}' is not installed", overlay_id))?;
+    let snapshot = installed
+        .snapshots
+        .first()
+        .ok_or_else(|| anyhow::anyhow!("Overlay '{}' has no cached archive", overlay_id))?;
+
+    info!("Reapplying overlay '{}'", overlay_id);
+    restore_localization_snapshot(
+        game_path,
+        metadata,
+        snapshot,
+        installed.components.as_deref(),
+        limits,
+        validation,
+    )?;
+    Ok(())
+}
+
+/// Rebuilds installed overlays on top of `base_id`, including overlays
+/// stacked on them, from their cached archives and returns the ones that
+/// could not be applied.
+pub fn reapply_overlays(
+    game_path: &PathBuf,
+    metadata: &InstalledMetadata,
+    base_id: &str,
+    limits: &ExtractionLimits,
+    validation: &ValidationSettings,
+) -> Vec<(String, anyhow::Error)> {
+    metadata
+        .dependent_overlays(base_id)
+        .into_iter()
+        .filter_map(|overlay_id| {
+            reapply_overlay(game_path, metadata, &overlay_id, limits, validation)
+                .err()
+                .map(|e| (overlay_id, e))
+        })
+        .collect()
+}
+
+pub fn install_composite(
+    game_path: &PathBuf,
+    composite: &CompositeLocalization,
+    validation: &ValidationSettings,
+) -> Result<Vec<ValidationIssue>, anyhow::Error> {
+    let sources = composite.source_directories(game_path)?;
+
+    let issues = install_to_game_directory(game_path, &composite.id, validation, |staging_path| {
+        debug!("Building composite localization in {:?}", staging_path);
+        composite::build_composite(&sources, staging_path)
+    })?;
+
+    info!(
+        "Successfully built composite localization '{}'",
+        composite.id
+    );
+    Ok(issues)
+}
+
+pub fn remove_composite(
+    game_path: &PathBuf,
+    metadata: &InstalledMetadata,
+    composite_id: &str,
+) -> Result<(), anyhow::Error> {
+    if !metadata.composites.contains_key(composite_id) {
+        return Err(anyhow::anyhow!(
+            "'{}' is not a composite localization",
+            composite_id
+        ));
+    }
+
+    let target_path = game_path
+        .join("LimbusCompany_Data")
+        .join("Lang")
+        .join(composite_id);
+
+    if target_path.exists() {
+        fs::remove_dir_all(&target_path).with_context(|| {
+            format!("Failed to remove composite localization '{}'", composite_id)
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Rebuilds composites that read from any of `changed_ids`, directly or
+/// through another composite, and returns the ones that could not be built.
+pub fn rebuild_composites(
+    game_path: &PathBuf,
+    metadata: &InstalledMetadata,
+    changed_ids: &[String],
+    validation: &ValidationSettings,
+) -> Vec<(String, anyhow::Error)> {
+    let mut affected: Vec<&CompositeLocalization> = Vec::new();
+    let mut changed = changed_ids.to_vec();
+
+    while let Some(id) = changed.pop() {
+        for composite in metadata.composites.values() {
+            if composite.depends_on(&id) && !affected.iter().any(|c| c.id == composite.id) {
+                affected.push(composite);
+                changed.push(composite.id.clone());
+            }
+        }
+    }
+    affected.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let mut failed = Vec::new();
+
+    // Composites are built after the composites they read from
+    while !affected.is_empty() {
+        let Some(position) = affected.iter().position(|composite| {
+            composite
+                .inputs()
+                .all(|input| !affected.iter().any(|other| other.id == *input))
+        }) else {
+            for composite in affected {
+                failed.push((
+                    composite.id.clone(),
+                    anyhow::anyhow!(
+                        "Composite localization '{}' depends on itself",
+                        composite.id
+                    ),
+                ));
+            }
+            break;
+        };
+        let composite = affected.remove(position);
+
+        info!(
+            "Rebuilding composite '{}' after its inputs changed",
+            composite.id
+        );
+
+        if let Err(e) = install_composite(game_path, composite, validation) {
+            failed.push((composite.id.clone(), e));
+        }
+    }
+
+    failed
+}
+
+/// Removes `Lang/<id>` but keeps its cached archives, so switching back to
+/// a profile that uses it does not download it again.
+pub fn stash_localization(
+    game_path: &PathBuf,
+    metadata: &mut InstalledMetadata,
+    localization_id: &str,
+) -> Result<(), anyhow::Error> {
+    let target_path = game_path
+        .join("LimbusCompany_Data")
+        .join("Lang")
+        .join(localization_id);
+
+    if target_path.exists() {
+        fs::remove_dir_all(&target_path)
+            .with_context(|| format!("Failed to remove localization '{}'", localization_id))?;
+    }
+
+    if let Some(installed) = metadata.installed.remove(localization_id) {
+        metadata
+            .stashed
+            .insert(localization_id.to_string(), installed);
+    }
+
+    info!("Stashed localization '{}'", localization_id);
     Ok(())
 }
 
+pub fn remove_localization_snapshots(
+    game_path: &PathBuf,
+    localization_id: &str,
+    snapshots: &[LocalizationSnapshot],
+) {
+    let cache_dir = archive_cache_directory(game_path, localization_id);
+
+    for snapshot in snapshots {
+        let archive_path = cache_dir.join(&snapshot.archive);
+        debug!("Removing cached archive {:?}", archive_path);
+
+        if let Err(e) = fs::remove_file(&archive_path) {
+            warn!("Failed to remove cached archive {:?}: {}", archive_path, e);
+        }
+    }
+}
+
 pub async fn uninstall_localization(
     game_path: &PathBuf,
     localization: &Localization,
@@ -322,6 +1086,13 @@ pub async fn uninstall_localization(
     fs::remove_dir_all(&target_path)
         .with_context(|| format!("Failed to uninstall localization '{}'", localization.id))?;
 
+    let cache_dir = archive_cache_directory(game_path, &localization.id);
+    if cache_dir.exists() {
+        fs::remove_dir_all(&cache_dir).with_context(|| {
+            format!("Failed to remove cached archives of '{}'", localization.id)
+        })?;
+    }
+
     Ok(())
 }
 
@@ -352,6 +1123,76 @@ pub async fn get_latest_version() -> Result<String, anyhow::Error> {
     Ok(tag_name.to_string())
 }
 
+fn game_config_path(game_path: &Path) -> PathBuf {
+    game_path
+        .join("LimbusCompany_Data")
+        .join("Lang")
+        .join("config.json")
+}
+
+pub fn get_active_localization(game_path: &PathBuf) -> Result<Option<String>, anyhow::Error> {
+    let config_path = game_config_path(game_path);
+
+    if !config_path.is_file() {
+        return Ok(None);
+    }
+
+    let config_content =
+        fs::read_to_string(&config_path).with_context(|| format!("Failed to read config file"))?;
+    let config: GameConfig = serde_json::from_str(config_content.trim_start_matches('\u{feff}'))
+        .with_context(|| format!("Failed to parse config file"))?;
+
+    Ok(Some(config.lang).filter(|lang| !lang.is_empty()))
+}
+
+/// Points the game at a built-in language or an installed or composite
+/// localization, keeping any other settings the game stores in the same file.
+pub fn set_active_localization(
+    game_path: &PathBuf,
+    metadata: &InstalledMetadata,
+    lang: &str,
+) -> Result<(), anyhow::Error> {
+    if lang.is_empty() || lang.starts_with('.') || lang.contains(['/', '\\']) {
+        return Err(anyhow::anyhow!("Invalid localization id '{}'", lang));
+    }
+
+    let is_installed = (metadata.installed.contains_key(lang)
+        || metadata.composites.contains_key(lang))
+        && game_path
+            .join("LimbusCompany_Data")
+            .join("Lang")
+            .join(lang)
+            .is_dir();
+
+    if !(is_installed || BUILTIN_LANGUAGES.contains(&lang)) {
+        return Err(anyhow::anyhow!("Localization '{}' is not installed", lang));
+    }
+
+    let config_path = game_config_path(game_path);
+    let mut config = fs::read_to_string(&config_path)
+        .ok()
+        .and_then(|content| {
+            serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(
+                content.trim_start_matches('\u{feff}'),
+            )
+            .ok()
+        })
+        .unwrap_or_default();
+
+    config.insert(
+        "lang".to_string(),
+        serde_json::Value::String(lang.to_string()),
+    );
+
+    let config_content = serde_json::to_string_pretty(&config)
+        .with_context(|| format!("Failed to serialize config file"))?;
+    fs::write(&config_path, config_content)
+        .with_context(|| format!("Failed to write config file"))?;
+
+    info!("Active localization set to '{}'", lang);
+    Ok(())
+}
+
 pub fn validate_game_config(game_path: &PathBuf) -> Result<(), anyhow::Error> {
     let config_path = game_path
         .join("LimbusCompany_Data")
@@ -381,7 +1222,7 @@ pub fn validate_game_config(game_path: &PathBuf) -> Result<(), anyhow::Error> {
                 .join("Lang")
                 .join(&config.lang);
 
-            if !active_localization.exists() {
+            if !active_localization.exists() && !BUILTIN_LANGUAGES.contains(&config.lang.as_str()) {
                 debug!("Active localization does not exist, deleting config file");
                 fs::remove_file(&config_path)
                     .with_context(|| format!("Failed to delete config file"))?;
@@ -410,7 +1251,7 @@ async fn download_localization_file(
     localization: &Localization,
     temp_dir: &tempfile::TempDir,
 ) -> Result<PathBuf, anyhow::Error> {
-    let download_path = temp_dir.path().join("localization.zip");
+    let download_path = temp_dir.path().join("localization.archive");
 
     let response = HTTP_CLIENT
         .get(&localization.url)
@@ -446,6 +1287,17 @@ async fn download_localization_file(
         return Err(anyhow::anyhow!("File size mismatch"));
     }
 
+    if let Some(expected_hash) = &localization.hash {
+        let calculated_hash = calculate_md5(&download_path)?;
+        if !calculated_hash.eq_ignore_ascii_case(expected_hash) {
+            return Err(anyhow::anyhow!(
+                "File hash mismatch (expected: {}, found: {})",
+                expected_hash,
+                calculated_hash
+            ));
+        }
+    }
+
     info!(
         "Successfully downloaded localization from: {}",
         &localization.url
@@ -453,140 +1305,463 @@ async fn download_localization_file(
     Ok(download_path)
 }
 
-fn extract_zip_archive(zip_path: &Path, extract_path: &Path) -> Result<(), anyhow::Error> {
-    let file = fs::File::open(zip_path).with_context(|| format!("Failed to open zip file"))?;
-
-    let mut archive =
-        ZipArchive::new(file).with_context(|| format!("Failed to read ZIP archive"))?;
-
-    for i in 0..archive.len() {
-        let mut file = archive
-            .by_index(i)
-            .with_context(|| format!("Error reading file in zip"))?;
+fn install_localization_archive(
+    game_path: &PathBuf,
+    metadata: &InstalledMetadata,
+    archive_path: &Path,
+    localization: &Localization,
+    components: Option<&[String]>,
+    limits: &ExtractionLimits,
+    validation: &ValidationSettings,
+) -> Result<Vec<ValidationIssue>, anyhow::Error> {
+    let filter = component_filter(localization, components)?;
+    let include = |path: &Path| filter.as_ref().map_or(true, |set| set.is_match(path));
+
+    if let Format::Overlay(base) = &localization.format {
+        return install_overlay_archive(
+            game_path,
+            metadata,
+            archive_path,
+            localization,
+            base,
+            &include,
+            limits,
+            validation,
+        );
+    }
 
-        let outpath = match file.enclosed_name() {
-            Some(path) => extract_path.join(path),
-            None => {
-                warn!("Entry {} has unsafe path, skipping.", i);
-                continue;
+    let entries = archive::list_archive_entries(archive_path, localization.archive_type)?;
+    let language_dir = match &localization.path {
+        Some(path) => {
+            let path = PathBuf::from(path);
+            if !entries.iter().any(|entry| entry.starts_with(&path)) {
+                return Err(anyhow::anyhow!(
+                    "Language folder '{}' not found in the archive",
+                    path.display()
+                ));
             }
-        };
+            path
+        }
+        None => find_language_directory(&entries, &localization.format)?,
+    };
+
+    install_to_game_directory(game_path, &localization.id, validation, |staging_path| {
+        debug!("Extracting localization to: {:?}", staging_path);
+        archive::extract_archive(
+            archive_path,
+            staging_path,
+            localization.archive_type,
+            limits,
+            &language_dir,
+            &include,
+        )
+    })
+}
 
-        extract_zip_entry(&mut file, &outpath)?;
+fn install_overlay_archive(
+    game_path: &PathBuf,
+    metadata: &InstalledMetadata,
+    archive_path: &Path,
+    localization: &Localization,
+    base: &OverlayBase,
+    include: &dyn Fn(&Path) -> bool,
+    limits: &ExtractionLimits,
+    validation: &ValidationSettings,
+) -> Result<Vec<ValidationIssue>, anyhow::Error> {
+    let base_path = game_path
+        .join("LimbusCompany_Data")
+        .join("Lang")
+        .join(&base.id);
+
+    let base_version = match metadata.installed.get(&base.id) {
+        Some(installed) if base_path.is_dir() => &installed.version,
+        _ => {
+            return Err(anyhow::anyhow!(
+                "Base localization '{}' is not installed",
+                base.id
+            ))
+        }
+    };
+
+    if let Some(versions) = &base.versions {
+        if !overlay::version_matches(base_version, versions) {
+            return Err(anyhow::anyhow!(
+                "Base localization '{}' version '{}' does not match '{}'",
+                base.id,
+                base_version,
+                versions
+            ));
+        }
     }
 
-    Ok(())
+    let temp_dir = create_temp_directory(&localization.id)?;
+    let overlay_path = temp_dir.path();
+
+    debug!("Extracting overlay to: {:?}", overlay_path);
+    archive::extract_archive(
+        archive_path,
+        overlay_path,
+        localization.archive_type,
+        limits,
+        Path::new(""),
+        include,
+    )?;
+
+    install_to_game_directory(game_path, &localization.id, validation, |staging_path| {
+        debug!("Copying base localization from {:?}", base_path);
+        copy_directory_contents(&base_path, staging_path)?;
+        overlay::apply_overlay(overlay_path, staging_path)
+    })
 }
 
-fn extract_zip_entry(file: &mut zip::read::ZipFile, outpath: &Path) -> Result<(), anyhow::Error> {
-    if file.name().ends_with('/') {
-        debug!("Creating directory: {:?}", outpath);
-        fs::create_dir_all(outpath)
-            .with_context(|| format!("Failed to create directory during extraction"))?;
-    } else {
-        debug!("Extracting file: {:?}", outpath);
-        if let Some(parent) = outpath.parent() {
-            if !parent.exists() {
-                fs::create_dir_all(parent).with_context(|| {
-                    format!("Failed to create parent directory during extraction")
+/// Builds a matcher for the files of the selected components, `None` means
+/// the whole localization is installed.
+fn component_filter(
+    localization: &Localization,
+    components: Option<&[String]>,
+) -> Result<Option<GlobSet>, anyhow::Error> {
+    let Some(selected) = components else {
+        return Ok(None);
+    };
+
+    if localization.components.is_empty() {
+        debug!(
+            "Localization '{}' has no components, installing everything",
+            localization.id
+        );
+        return Ok(None);
+    }
+
+    let mut builder = GlobSetBuilder::new();
+    let mut matched = 0;
+
+    for id in selected {
+        let Some(component) = localization.components.iter().find(|c| &c.id == id) else {
+            warn!(
+                "Localization '{}' has no component '{}', skipping",
+                localization.id, id
+            );
+            continue;
+        };
+
+        for pattern in &component.paths {
+            let glob = GlobBuilder::new(pattern)
+                .literal_separator(true)
+                .build()
+                .with_context(|| {
+                    format!("Invalid path '{}' in component '{}'", pattern, component.id)
                 })?;
-            }
+            builder.add(glob);
         }
+        matched += 1;
+    }
 
-        let mut outfile = fs::File::create(outpath)
-            .with_context(|| format!("Failed to create file during extraction"))?;
-
-        io::copy(file, &mut outfile)
-            .with_context(|| format!("Failed to copy file during extraction"))?;
+    if matched == 0 {
+        return Err(anyhow::anyhow!(
+            "None of the selected components exist in localization '{}'",
+            localization.id
+        ));
     }
 
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        if let Some(mode) = file.unix_mode() {
-            fs::set_permissions(outpath, fs::Permissions::from_mode(mode))
-                .with_context(|| format!("Failed to set permissions"))?;
-        }
+    Ok(Some(builder.build().with_context(|| {
+        format!("Failed to build component filter")
+    })?))
+}
+
+fn archive_cache_directory(game_path: &Path, localization_id: &str) -> PathBuf {
+    game_path.join(ARCHIVE_CACHE_DIR_NAME).join(localization_id)
+}
+
+fn cache_localization_archive(
+    game_path: &PathBuf,
+    localization: &Localization,
+    archive_path: &Path,
+) -> Result<LocalizationSnapshot, anyhow::Error> {
+    let cache_dir = archive_cache_directory(game_path, &localization.id);
+    fs::create_dir_all(&cache_dir)
+        .with_context(|| format!("Failed to create archive cache directory {:?}", cache_dir))?;
+
+    let archive_type = archive::detect_archive_type(archive_path, localization.archive_type)?;
+    let archive_name = format!(
+        "{}-{}.{}",
+        sanitize_file_name(&localization.version),
+        &version_hash(&localization.version)[..8],
+        archive_type.extension()
+    );
+    let cached_path = cache_dir.join(&archive_name);
+    remove_shared_file(&cached_path)?;
+
+    debug!("Caching archive {:?} -> {:?}", archive_path, cached_path);
+    fs::copy(archive_path, &cached_path)
+        .with_context(|| format!("Failed to cache archive to {:?}", cached_path))?;
+
+    Ok(LocalizationSnapshot {
+        version: localization.version.clone(),
+        archive: archive_name,
+        localization: localization.clone(),
+    })
+}
+
+/// Different versions may sanitize to the same name, the hash keeps their
+/// archives apart.
+fn version_hash(version: &str) -> String {
+    format!("{:x}", Md5::digest(version.as_bytes()))
+}
+
+/// Caches a sub-pack with the archive another pack of the same bundle was
+/// cached with. The file is hard linked where possible, so the bundle is
+/// stored once and evicting one pack leaves the others intact.
+fn share_cached_archive(
+    game_path: &Path,
+    localization: &Localization,
+    shared: &LocalizationSnapshot,
+) -> Result<LocalizationSnapshot, anyhow::Error> {
+    let shared_path =
+        archive_cache_directory(game_path, &shared.localization.id).join(&shared.archive);
+    let cache_dir = archive_cache_directory(game_path, &localization.id);
+    fs::create_dir_all(&cache_dir)
+        .with_context(|| format!("Failed to create archive cache directory {:?}", cache_dir))?;
+
+    let cached_path = cache_dir.join(&shared.archive);
+    remove_shared_file(&cached_path)?;
+
+    debug!(
+        "Linking cached archive {:?} -> {:?}",
+        shared_path, cached_path
+    );
+    if let Err(e) = fs::hard_link(&shared_path, &cached_path) {
+        debug!("Failed to link cached archive, copying instead: {}", e);
+        fs::copy(&shared_path, &cached_path)
+            .with_context(|| format!("Failed to cache archive to {:?}", cached_path))?;
     }
 
+    Ok(LocalizationSnapshot {
+        version: localization.version.clone(),
+        archive: shared.archive.clone(),
+        localization: localization.clone(),
+    })
+}
+
+/// Cached archives may be hard links, writing through one would change the
+/// archive of every pack sharing it.
+fn remove_shared_file(path: &Path) -> Result<(), anyhow::Error> {
+    if path.exists() {
+        fs::remove_file(path).with_context(|| format!("Failed to remove {:?}", path))?;
+    }
     Ok(())
 }
 
-fn find_language_directory(extract_path: &Path, format: &Format) -> Result<PathBuf, anyhow::Error> {
+fn sanitize_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn find_language_directory(entries: &[PathBuf], format: &Format) -> Result<PathBuf, anyhow::Error> {
     match format {
-        Format::Compatible => find_language_dir(extract_path),
-        Format::Auto => find_language_dir(extract_path),
+        Format::Compatible => find_language_dir(entries),
+        Format::Auto => find_language_dir(entries),
         Format::New => {
-            debug!(
-                "Using 'new' format, language directory is root: {:?}",
-                extract_path
-            );
-            Ok(extract_path.to_path_buf())
+            debug!("Using 'new' format, language directory is archive root");
+            Ok(PathBuf::new())
         }
+        Format::Overlay(_) => Ok(PathBuf::new()),
         Format::Unknown(unknown) => {
             Err(anyhow::anyhow!("Unknown localization format: {}", unknown))
         }
     }
 }
 
-fn find_language_dir(extract_path: &Path) -> Result<PathBuf, anyhow::Error> {
-    fn find_story_data_dir(path: &Path) -> Option<PathBuf> {
-        if path.join("StoryData").is_dir() {
-            return Some(path.to_path_buf());
-        }
+#[derive(Default, Debug)]
+struct LanguageDirCandidate {
+    marker_directories: BTreeSet<String>,
+    marker_files: usize,
+    json_files: usize,
+}
 
-        if path.is_dir() {
-            for entry in fs::read_dir(path).ok()? {
-                if let Ok(entry) = entry {
-                    let entry_path = entry.path();
-                    if let Some(found) = find_story_data_dir(&entry_path) {
-                        return Some(found);
-                    }
-                }
+impl LanguageDirCandidate {
+    fn score(&self) -> usize {
+        self.marker_directories.len() * 10 + self.marker_files * 3 + self.json_files.min(50) / 5
+    }
+}
+
+/// Scores every folder of the archive by the language folder markers it
+/// contains and picks the best one, refusing to guess between equals.
+fn find_language_dir(entries: &[PathBuf]) -> Result<PathBuf, anyhow::Error> {
+    let mut candidates: BTreeMap<&Path, LanguageDirCandidate> = BTreeMap::new();
+
+    for entry in entries {
+        for ancestor in entry.ancestors() {
+            let (Some(parent), Some(name)) = (ancestor.parent(), ancestor.file_name()) else {
+                continue;
+            };
+
+            if let Some(marker) = LANGUAGE_MARKER_DIRECTORIES
+                .iter()
+                .find(|marker| name == **marker)
+            {
+                candidates
+                    .entry(parent)
+                    .or_default()
+                    .marker_directories
+                    .insert(marker.to_string());
             }
         }
 
-        None
+        let is_json = entry
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+
+        if let (true, Some(parent), Some(stem)) = (
+            is_json,
+            entry.parent(),
+            entry.file_stem().and_then(|stem| stem.to_str()),
+        ) {
+            let candidate = candidates.entry(parent).or_default();
+            candidate.json_files += 1;
+
+            if LANGUAGE_MARKER_FILES
+                .iter()
+                .any(|marker| stem.ends_with(marker))
+            {
+                candidate.marker_files += 1;
+            }
+        }
     }
 
-    match find_story_data_dir(extract_path) {
-        Some(path) => {
-            debug!("Found compatible language directory: {:?}", path);
-            Ok(path)
-        }
-        None => Err(anyhow::anyhow!(
-            "Could not find language directory with StoryData in '{:?}'.",
-            extract_path
+    let mut candidates: Vec<_> = candidates
+        .into_iter()
+        .filter(|(_, candidate)| !candidate.marker_directories.is_empty())
+        .map(|(path, candidate)| (path, candidate.score()))
+        .collect();
+    candidates.sort_by(|(a_path, a_score), (b_path, b_score)| {
+        b_score.cmp(a_score).then_with(|| a_path.cmp(b_path))
+    });
+
+    debug!("Language directory candidates: {:?}", candidates);
+
+    match candidates.as_slice() {
+        [] => Err(anyhow::anyhow!(
+            "Could not find language directory with StoryData in the archive."
         )),
+        [(_, best_score), rest @ ..]
+            if rest.first().is_some_and(|(_, score)| score == best_score) =>
+        {
+            let listing = candidates
+                .iter()
+                .take_while(|(_, score)| score == best_score)
+                .map(|(path, score)| {
+                    let path = if path.as_os_str().is_empty() {
+                        Path::new(".")
+                    } else {
+                        path
+                    };
+                    format!("'{}' (score {})", path.display(), score)
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            Err(anyhow::anyhow!(
+                "Found several equally likely language directories: {}",
+                listing
+            ))
+        }
+        [(path, score), ..] => {
+            debug!(
+                "Found compatible language directory: {:?} (score {})",
+                path, score
+            );
+            Ok(path.to_path_buf())
+        }
     }
 }
 
-fn install_to_game_directory(
+/// Fills a staging directory next to `Lang/<id>`, validates its JSON files
+/// and swaps it into place, so a failed install leaves the previous version
+/// untouched.
+fn install_to_game_directory<F>(
     game_path: &PathBuf,
-    language_dir: &Path,
-    localization: &Localization,
-) -> Result<(), anyhow::Error> {
+    localization_id: &str,
+    validation: &ValidationSettings,
+    fill: F,
+) -> Result<Vec<ValidationIssue>, anyhow::Error>
+where
+    F: FnOnce(&Path) -> Result<(), anyhow::Error>,
+{
     let target_base_path = game_path.join("LimbusCompany_Data").join("Lang");
-    let target_path = target_base_path.join(&localization.id);
+    let target_path = target_base_path.join(localization_id);
+    let staging_path = target_base_path.join(format!(".{}.staging", localization_id));
+    let backup_path = target_base_path.join(format!(".{}.old", localization_id));
 
     debug!("Target installation path: {:?}", target_path);
 
-    if target_path.exists() {
-        info!("Removing existing localization at {:?}", target_path);
-        fs::remove_dir_all(&target_path)
-            .with_context(|| format!("Failed to remove existing localization directory"))?;
+    for leftover in [&staging_path, &backup_path] {
+        if leftover.exists() {
+            debug!("Removing leftover directory {:?}", leftover);
+            fs::remove_dir_all(leftover)
+                .with_context(|| format!("Failed to remove leftover directory {:?}", leftover))?;
+        }
+    }
+
+    fs::create_dir_all(&staging_path)
+        .with_context(|| format!("Failed to create staging directory"))?;
+
+    let issues = match fill(&staging_path)
+        .and_then(|_| validation::validate_directory(&staging_path, validation))
+    {
+        Ok(issues) => issues,
+        Err(e) => {
+            fs::remove_dir_all(&staging_path).ok();
+            return Err(e);
+        }
+    };
+
+    if validation.refuse_invalid && validation::has_errors(&issues) {
+        fs::remove_dir_all(&staging_path).ok();
+
+        let errors = issues
+            .iter()
+            .filter(|issue| issue.severity == validation::Severity::Error)
+            .map(|issue| issue.to_string())
+            .collect::<Vec<_>>();
+
+        return Err(anyhow::anyhow!(
+            "Localization '{}' has {} broken files: {}",
+            localization_id,
+            errors.len(),
+            errors.join("; ")
+        ));
     }
 
-    fs::create_dir_all(&target_base_path)
-        .with_context(|| format!("Failed to create base Lang directory"))?;
+    if target_path.exists() {
+        info!("Replacing existing localization at {:?}", target_path);
+        if let Err(e) = fs::rename(&target_path, &backup_path) {
+            fs::remove_dir_all(&staging_path).ok();
+            return Err(e)
+                .with_context(|| format!("Failed to move existing localization directory"));
+        }
+    }
 
-    fs::create_dir(&target_path)
-        .with_context(|| format!("Failed to create target localization directory"))?;
+    if let Err(e) = fs::rename(&staging_path, &target_path) {
+        if backup_path.exists() {
+            fs::rename(&backup_path, &target_path).ok();
+        }
+        fs::remove_dir_all(&staging_path).ok();
+        return Err(e).with_context(|| format!("Failed to move staged localization into place"));
+    }
 
-    debug!("Moving files from {:?} to {:?}", language_dir, target_path);
-    copy_directory_contents(language_dir, &target_path)?;
+    if backup_path.exists() {
+        fs::remove_dir_all(&backup_path)
+            .with_context(|| format!("Failed to remove previous localization directory"))?;
+    }
 
-    Ok(())
+    Ok(issues)
 }
 
 fn copy_directory_contents(src_dir: &Path, dest_dir: &Path) -> Result<(), anyhow::Error> {
@@ -595,14 +1770,7 @@ fn copy_directory_contents(src_dir: &Path, dest_dir: &Path) -> Result<(), anyhow
     {
         let entry = entry.with_context(|| format!("Error reading entry in language dir"))?;
         let source_path = entry.path();
-        let file_name = entry.file_name();
-
-        if file_name == "localization.zip" {
-            debug!("Skipping localization.zip file");
-            continue;
-        }
-
-        let destination_path = dest_dir.join(&file_name);
+        let destination_path = dest_dir.join(entry.file_name());
 
         debug!("Copying {:?} -> {:?}", source_path, destination_path);
 
@@ -0,0 +1,305 @@
+use anyhow::Context;
+use log::{debug, warn};
+use serde::de::{DeserializeSeed, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct ValidationSettings {
+    pub normalize: bool,      // Strip BOMs and convert CRLF line endings in place
+    pub refuse_invalid: bool, // Keep the previous version if any file has errors
+}
+
+impl Default for ValidationSettings {
+    fn default() -> Self {
+        Self {
+            normalize: true,
+            refuse_invalid: false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueKind {
+    Bom,
+    CrlfLineEndings,
+    InvalidUtf8,
+    InvalidJson,
+    DuplicateKey,
+}
+
+impl IssueKind {
+    fn severity(&self) -> Severity {
+        match self {
+            IssueKind::InvalidUtf8 | IssueKind::InvalidJson => Severity::Error,
+            IssueKind::Bom | IssueKind::CrlfLineEndings | IssueKind::DuplicateKey => {
+                Severity::Warning
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ValidationIssue {
+    pub file: String, // Relative to the localization folder
+    pub kind: IssueKind,
+    pub severity: Severity,
+    pub message: String,
+    pub fixed: bool, // Normalized in place
+}
+
+impl ValidationIssue {
+    fn new(file: &str, kind: IssueKind, message: String) -> Self {
+        Self {
+            file: file.to_string(),
+            kind,
+            severity: kind.severity(),
+            message,
+            fixed: false,
+        }
+    }
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.file, self.message)
+    }
+}
+
+pub fn has_errors(issues: &[ValidationIssue]) -> bool {
+    issues.iter().any(|issue| issue.severity == Severity::Error)
+}
+
+/// Parses every JSON file below `dir` and reports anything the game could
+/// choke on. BOMs and CRLF line endings are fixed when `normalize` is set.
+pub fn validate_directory(
+    dir: &Path,
+    settings: &ValidationSettings,
+) -> Result<Vec<ValidationIssue>, anyhow::Error> {
+    let mut files = Vec::new();
+    collect_json_files(dir, &mut files)?;
+    files.sort();
+
+    let mut issues = Vec::new();
+    for path in files {
+        let relative = path
+            .strip_prefix(dir)
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .replace('\\', "/");
+
+        issues.extend(validate_file(&path, &relative, settings)?);
+    }
+
+    debug!("Validated {:?}, found {} issues", dir, issues.len());
+    Ok(issues)
+}
+
+fn collect_json_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), anyhow::Error> {
+    for entry in fs::read_dir(dir).with_context(|| format!("Failed to read directory {:?}", dir))? {
+        let entry = entry.with_context(|| format!("Error reading entry in {:?}", dir))?;
+        let path = entry.path();
+
+        if path.is_dir() {
+            collect_json_files(&path, files)?;
+        } else if path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+        {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_file(
+    path: &Path,
+    relative: &str,
+    settings: &ValidationSettings,
+) -> Result<Vec<ValidationIssue>, anyhow::Error> {
+    let bytes = fs::read(path).with_context(|| format!("Failed to read JSON file {:?}", path))?;
+    let mut issues = Vec::new();
+
+    let has_bom = bytes.starts_with(UTF8_BOM);
+    if has_bom {
+        issues.push(ValidationIssue::new(
+            relative,
+            IssueKind::Bom,
+            "File starts with a UTF-8 byte order mark".to_string(),
+        ));
+    }
+
+    let text = match std::str::from_utf8(&bytes[if has_bom { UTF8_BOM.len() } else { 0 }..]) {
+        Ok(text) => text,
+        Err(e) => {
+            issues.push(ValidationIssue::new(
+                relative,
+                IssueKind::InvalidUtf8,
+                format!("Invalid UTF-8 at byte {}", e.valid_up_to()),
+            ));
+            return Ok(issues);
+        }
+    };
+
+    let has_crlf = text.contains("\r\n");
+    if has_crlf {
+        issues.push(ValidationIssue::new(
+            relative,
+            IssueKind::CrlfLineEndings,
+            "File uses CRLF line endings".to_string(),
+        ));
+    }
+
+    let mut duplicates = Vec::new();
+    let mut deserializer = serde_json::Deserializer::from_str(text);
+    let parsed = KeyChecker {
+        path: String::new(),
+        duplicates: &mut duplicates,
+    }
+    .deserialize(&mut deserializer)
+    .and_then(|_| deserializer.end());
+
+    if let Err(e) = parsed {
+        issues.push(ValidationIssue::new(
+            relative,
+            IssueKind::InvalidJson,
+            format!("Invalid JSON: {}", e),
+        ));
+    }
+
+    issues.extend(duplicates.into_iter().map(|key| {
+        ValidationIssue::new(
+            relative,
+            IssueKind::DuplicateKey,
+            format!("Duplicate key '{}'", key),
+        )
+    }));
+
+    if settings.normalize && (has_bom || has_crlf) {
+        debug!("Normalizing {:?}", path);
+        fs::write(path, text.replace("\r\n", "\n"))
+            .with_context(|| format!("Failed to normalize JSON file {:?}", path))?;
+
+        for issue in issues.iter_mut() {
+            if matches!(issue.kind, IssueKind::Bom | IssueKind::CrlfLineEndings) {
+                issue.fixed = true;
+            }
+        }
+    }
+
+    for issue in &issues {
+        warn!("{}", issue);
+    }
+
+    Ok(issues)
+}
+
+/// Walks a JSON document without building it and records keys that appear
+/// more than once in the same object, which `serde_json::Value` would hide.
+struct KeyChecker<'a> {
+    path: String,
+    duplicates: &'a mut Vec<String>,
+}
+
+impl<'de> DeserializeSeed<'de> for KeyChecker<'_> {
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for KeyChecker<'_> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any JSON value")
+    }
+
+    fn visit_bool<E>(self, _: bool) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_i64<E>(self, _: i64) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_u64<E>(self, _: u64) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_f64<E>(self, _: f64) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_str<E>(self, _: &str) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut index = 0;
+        while seq
+            .next_element_seed(KeyChecker {
+                path: format!("{}[{}]", self.path, index),
+                duplicates: &mut *self.duplicates,
+            })?
+            .is_some()
+        {
+            index += 1;
+        }
+
+        Ok(())
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut keys = HashSet::new();
+
+        while let Some(key) = map.next_key::<String>()? {
+            let path = if self.path.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", self.path, key)
+            };
+
+            if !keys.insert(key) {
+                self.duplicates.push(path.clone());
+            }
+
+            map.next_value_seed(KeyChecker {
+                path,
+                duplicates: &mut *self.duplicates,
+            })?;
+        }
+
+        Ok(())
+    }
+}
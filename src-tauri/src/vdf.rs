@@ -0,0 +1,288 @@
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// A node of a Valve KeyValues (VDF/ACF) document. Objects keep their
+/// entries in file order, duplicate keys included.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Value {
+    String(String),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    /// First entry named `key`, compared case-insensitively like Steam does.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.entries()
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value)
+    }
+
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.get(key).and_then(Value::as_str)
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(value) => Some(value),
+            Value::Object(_) => None,
+        }
+    }
+
+    /// Entries of an object, empty for a string.
+    pub fn entries(&self) -> &[(String, Value)] {
+        match self {
+            Value::Object(entries) => entries,
+            Value::String(_) => &[],
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Token {
+    Text(String),
+    Open,
+    Close,
+}
+
+struct Tokenizer<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+}
+
+impl Tokenizer<'_> {
+    fn next_token(&mut self) -> Result<Option<Token>, anyhow::Error> {
+        loop {
+            let Some(&c) = self.chars.peek() else {
+                return Ok(None);
+            };
+
+            match c {
+                '\n' => {
+                    self.line += 1;
+                    self.chars.next();
+                }
+                c if c.is_whitespace() => {
+                    self.chars.next();
+                }
+                '/' => {
+                    self.chars.next();
+                    if self.chars.peek() != Some(&'/') {
+                        return Err(anyhow::anyhow!("Unexpected '/' on line {}", self.line));
+                    }
+                    while self.chars.peek().is_some_and(|&c| c != '\n') {
+                        self.chars.next();
+                    }
+                }
+                // Platform conditionals such as [$WIN32] are ignored
+                '[' => while self.chars.next().is_some_and(|c| c != ']') {},
+                '{' => {
+                    self.chars.next();
+                    return Ok(Some(Token::Open));
+                }
+                '}' => {
+                    self.chars.next();
+                    return Ok(Some(Token::Close));
+                }
+                '"' => {
+                    self.chars.next();
+                    return self.quoted().map(|text| Some(Token::Text(text)));
+                }
+                _ => return Ok(Some(Token::Text(self.unquoted()))),
+            }
+        }
+    }
+
+    fn quoted(&mut self) -> Result<String, anyhow::Error> {
+        let start_line = self.line;
+        let mut text = String::new();
+
+        loop {
+            match self.chars.next() {
+                Some('"') => return Ok(text),
+                Some('\\') => match self.chars.next() {
+                    Some('\\') => text.push('\\'),
+                    Some('"') => text.push('"'),
+                    Some('n') => text.push('\n'),
+                    Some('t') => text.push('\t'),
+                    Some(c) => {
+                        text.push('\\');
+                        text.push(c);
+                    }
+                    None => break,
+                },
+                Some(c) => {
+                    if c == '\n' {
+                        self.line += 1;
+                    }
+                    text.push(c);
+                }
+                None => break,
+            }
+        }
+
+        Err(anyhow::anyhow!(
+            "Unterminated string starting on line {}",
+            start_line
+        ))
+    }
+
+    fn unquoted(&mut self) -> String {
+        let mut text = String::new();
+
+        while let Some(&c) = self.chars.peek() {
+            if c.is_whitespace() || matches!(c, '"' | '{' | '}') {
+                break;
+            }
+            text.push(c);
+            self.chars.next();
+        }
+
+        text
+    }
+}
+
+/// Parses a KeyValues document into an object holding its top-level
+/// entries, usually a single one such as `libraryfolders` or `AppState`.
+pub fn parse(input: &str) -> Result<Value, anyhow::Error> {
+    let mut tokenizer = Tokenizer {
+        chars: input.trim_start_matches('\u{feff}').chars().peekable(),
+        line: 1,
+    };
+
+    let entries = parse_entries(&mut tokenizer, false)?;
+    Ok(Value::Object(entries))
+}
+
+fn parse_entries(
+    tokenizer: &mut Tokenizer,
+    nested: bool,
+) -> Result<Vec<(String, Value)>, anyhow::Error> {
+    let mut entries = Vec::new();
+
+    loop {
+        let key = match tokenizer.next_token()? {
+            Some(Token::Text(key)) => key,
+            Some(Token::Close) if nested => return Ok(entries),
+            None if !nested => return Ok(entries),
+            Some(Token::Close) => {
+                return Err(anyhow::anyhow!(
+                    "Unexpected '}}' on line {}",
+                    tokenizer.line
+                ))
+            }
+            Some(Token::Open) => {
+                return Err(anyhow::anyhow!("Expected a key on line {}", tokenizer.line))
+            }
+            None => return Err(anyhow::anyhow!("Missing '}}' at end of file")),
+        };
+
+        let value = match tokenizer.next_token()? {
+            Some(Token::Text(value)) => Value::String(value),
+            Some(Token::Open) => Value::Object(parse_entries(tokenizer, true)?),
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Missing value for '{}' on line {}",
+                    key,
+                    tokenizer.line
+                ))
+            }
+        };
+
+        entries.push((key, value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_nested_objects() {
+        let root = parse(
+            r#"
+            "AppState"
+            {
+                "appid"     "1973530"
+                "UserConfig"
+                {
+                    "language"  "english"
+                }
+            }
+            "#,
+        )
+        .unwrap();
+
+        let app_state = root.get("AppState").unwrap();
+        assert_eq!(app_state.get_str("appid"), Some("1973530"));
+        assert_eq!(
+            app_state.get("UserConfig").unwrap().get_str("language"),
+            Some("english")
+        );
+    }
+
+    #[test]
+    fn unescapes_windows_paths() {
+        let root = parse(r#""path" "D:\\SteamLibrary\\""#).unwrap();
+        assert_eq!(root.get_str("path"), Some(r"D:\SteamLibrary\"));
+    }
+
+    #[test]
+    fn keeps_unknown_escapes() {
+        let root = parse(r#""path" "C:\Games""#).unwrap();
+        assert_eq!(root.get_str("path"), Some(r"C:\Games"));
+    }
+
+    #[test]
+    fn allows_entries_sharing_a_line() {
+        let root = parse(r#""a" { "b" "1" "c" "2" } "d" "3""#).unwrap();
+
+        assert_eq!(root.get("a").unwrap().get_str("b"), Some("1"));
+        assert_eq!(root.get("a").unwrap().get_str("c"), Some("2"));
+        assert_eq!(root.get_str("d"), Some("3"));
+    }
+
+    #[test]
+    fn skips_comments_and_conditionals() {
+        let root =
+            parse("// header\n\"a\" \"1\" [$WIN32] // trailing\n\"b\" { \"c\" \"2\" } [!$OSX]\n")
+                .unwrap();
+
+        assert_eq!(root.get_str("a"), Some("1"));
+        assert_eq!(root.get("b").unwrap().get_str("c"), Some("2"));
+    }
+
+    #[test]
+    fn reads_unquoted_tokens() {
+        let root = parse("AppState { appid 1973530 }").unwrap();
+        assert_eq!(
+            root.get("AppState").unwrap().get_str("appid"),
+            Some("1973530")
+        );
+    }
+
+    #[test]
+    fn matches_keys_case_insensitively() {
+        let root = parse(r#""AppState" { "InstallDir" "Limbus Company" }"#).unwrap();
+        assert_eq!(
+            root.get("appstate").unwrap().get_str("installdir"),
+            Some("Limbus Company")
+        );
+    }
+
+    #[test]
+    fn keeps_duplicate_keys_in_order() {
+        let root = parse(r#""a" "1" "a" "2""#).unwrap();
+
+        assert_eq!(root.get_str("a"), Some("1"));
+        assert_eq!(root.entries().len(), 2);
+    }
+
+    #[test]
+    fn rejects_malformed_documents() {
+        assert!(parse(r#""a" { "b" "1""#).is_err());
+        assert!(parse(r#""a" "1" }"#).is_err());
+        assert!(parse(r#""a""#).is_err());
+        assert!(parse(r#""a" "unterminated"#).is_err());
+    }
+}
@@ -2,8 +2,9 @@ import { useEffect } from "react";
 import { listen } from "@tauri-apps/api/event";
 import { useQueryClient } from "@tanstack/react-query";
 import i18n from "@/i18n";
-import { AppState } from "@/stores/models";
+import { AppState, GameUpdatedReport } from "@/stores/models";
 import { useAppState } from "@/hooks/use-app-state";
+import { toastError, toastInfo } from "@/components/toast/toast";
 
 export function useTauriQuerySync() {
   const queryClient = useQueryClient();
@@ -34,6 +35,43 @@ export function useTauriQuerySync() {
   }, [queryClient]);
 }
 
+export function useGameUpdateNotice() {
+  useEffect(() => {
+    const unlisten = listen<GameUpdatedReport>("game_updated", (event) => {
+      const { build, localizations } = event.payload;
+
+      toastInfo(i18n.t("gameUpdated.title", { build }));
+
+      localizations.forEach((result) => {
+        if (result.error) {
+          toastError(
+            i18n.t("gameUpdated.repairFailed", { localization: result.id })
+          );
+        } else if (result.repaired) {
+          toastInfo(
+            i18n.t("gameUpdated.repaired", { localization: result.id })
+          );
+        }
+
+        if (result.supported === false) {
+          toastInfo(
+            result.supported_update
+              ? i18n.t("gameUpdated.supportedUpdate", {
+                  localization: result.id,
+                  version: result.supported_update,
+                })
+              : i18n.t("gameUpdated.unsupported", { localization: result.id })
+          );
+        }
+      });
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+}
+
 export function useLanguageSync() {
   const { data: appState } = useAppState();
   const language = appState?.settings?.language;
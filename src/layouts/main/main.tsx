@@ -1,11 +1,16 @@
 import Navbar from "@/components/navbar/navbar";
 import { Outlet } from "react-router";
 import styles from "./main.module.css";
-import { useTauriQuerySync, useLanguageSync } from "@/hooks/use-tauri-events";
+import {
+  useTauriQuerySync,
+  useLanguageSync,
+  useGameUpdateNotice,
+} from "@/hooks/use-tauri-events";
 
 function Main() {
   useTauriQuerySync();
   useLanguageSync();
+  useGameUpdateNotice();
 
   return (
     <div className={styles.container}>
@@ -26,6 +26,21 @@ export interface AppState {
   installed_metadata: InstalledMetadata | null;
 }
 
+export interface BuildCheckResult {
+  id: string;
+  version: string;
+  repaired: boolean;
+  error: string | null;
+  supported: boolean | null;
+  supported_update: string | null;
+}
+
+export interface GameUpdatedReport {
+  previous_build: string;
+  build: string;
+  localizations: BuildCheckResult[];
+}
+
 export interface AvailableLocalizations {
   localizations: Localization[];
 }